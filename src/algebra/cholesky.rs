@@ -0,0 +1,286 @@
+use num::Float;
+use rayon::prelude::*;
+use thiserror::Error;
+
+use crate::{
+    backend::{self, Backend},
+    structures::{
+        matrix_simd::{MatrixOpError, SimdMatrix},
+        scalar::SimdScalar,
+        vector_simd::SimdVector,
+    },
+};
+
+/// `A = LLᵀ` factorization of a symmetric positive definite matrix, from
+/// `SimdMatrix::cholesky`.
+///
+/// The factor is stored as `Lᵀ`, whose rows are the columns of `L`, so both
+/// the factorization and the solves work on whole rows.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cholesky<T: SimdScalar + Float = f32> {
+    pub(crate) upper: SimdMatrix<T>,
+}
+
+/// `A = LDLᵀ` factorization of a symmetric positive semidefinite matrix,
+/// from `SimdMatrix::ldlt`, with `L` unit lower triangular and stored as
+/// `Lᵀ` like `Cholesky`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LDLT<T: SimdScalar + Float = f32> {
+    upper: SimdMatrix<T>,
+    diagonal: SimdVector<T>,
+}
+
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+pub enum CholeskyError {
+    #[error("Cholesky decomposition needs a square matrix, but was given a {rows}x{columns} one")]
+    NotSquare { rows: usize, columns: usize },
+    #[error("Matrix is not positive definite: pivot {pivot} is not positive")]
+    NotPositiveDefinite { pivot: usize },
+    #[error("Matrix is not positive semidefinite: pivot {pivot} is negative")]
+    NotPositiveSemidefinite { pivot: usize },
+    #[error(transparent)]
+    DimensionMismatch(#[from] MatrixOpError),
+}
+
+impl<T: SimdScalar + Float> SimdMatrix<T> {
+    /// Cholesky factorization. Each step turns the pivot row into a row of
+    /// `Lᵀ` and subtracts its outer product from the rows below it in
+    /// parallel, one SIMD `axpy` per row.
+    ///
+    /// A pivot within `n * epsilon * max|a_ii|` of zero counts as a failure,
+    /// so matrices that are only semidefinite to working precision are
+    /// rejected rather than factored into a meaningless `L`. Only the upper
+    /// triangle is read: symmetry is assumed, not checked.
+    ///
+    /// Runs on the current backend, which is this SIMD elimination unless the
+    /// backend forwards it, e.g. to LAPACK.
+    pub fn cholesky(&self) -> Result<Cholesky<T>, CholeskyError> {
+        self.check_square()?;
+        backend::with_current(|i| i.cholesky(self))
+    }
+
+    /// The SIMD factorization behind `cholesky`, for a square matrix, with the
+    /// row updates on `backend`.
+    pub(crate) fn simd_cholesky<B: Backend<T> + ?Sized>(
+        &self,
+        backend: &B,
+    ) -> Result<Cholesky<T>, CholeskyError> {
+        let tolerance = self.pivot_tolerance();
+        let mut upper = self.clone();
+        for k in 0..self.row_size {
+            let pivot = upper.matrix[k][k];
+            if pivot.is_nan() || pivot <= tolerance {
+                return Err(CholeskyError::NotPositiveDefinite { pivot: k });
+            }
+            let (done, remaining) = upper.matrix.split_at_mut(k + 1);
+            let row = &mut done[k];
+            *row /= pivot.sqrt();
+            row.as_mut_slice()[..k].fill(T::ZERO);
+            eliminate_below(backend, row, remaining, k, T::ONE);
+        }
+        Ok(Cholesky { upper })
+    }
+
+    /// `LDLᵀ` factorization, which unlike `cholesky` accepts singular
+    /// positive semidefinite matrices. A pivot within the same tolerance of
+    /// zero is stored as an exact zero in `D` and eliminates nothing; a more
+    /// negative one means the matrix is indefinite.
+    ///
+    /// Only the upper triangle is read: symmetry is assumed, not checked.
+    pub fn ldlt(&self) -> Result<LDLT<T>, CholeskyError> {
+        self.check_square()?;
+        backend::with_current(|backend| self.simd_ldlt(backend))
+    }
+
+    /// `ldlt` with the row updates on `backend`.
+    fn simd_ldlt(&self, backend: &dyn Backend<T>) -> Result<LDLT<T>, CholeskyError> {
+        let size = self.row_size;
+        let tolerance = self.pivot_tolerance();
+
+        let mut upper = self.clone();
+        let mut diagonal = SimdVector::zeros(size);
+        for k in 0..size {
+            let pivot = upper.matrix[k][k];
+            if pivot.is_nan() || pivot < -tolerance {
+                return Err(CholeskyError::NotPositiveSemidefinite { pivot: k });
+            }
+            let (done, remaining) = upper.matrix.split_at_mut(k + 1);
+            let row = &mut done[k];
+            if pivot <= tolerance {
+                *row = SimdVector::zeros(size);
+            } else {
+                diagonal[k] = pivot;
+                *row /= pivot;
+                row.as_mut_slice()[..k].fill(T::ZERO);
+                eliminate_below(backend, row, remaining, k, pivot);
+            }
+            row[k] = T::ONE;
+        }
+        Ok(LDLT { upper, diagonal })
+    }
+
+    pub(crate) fn pivot_tolerance(&self) -> T {
+        let largest = (0..self.row_size).fold(T::ZERO, |acc, i| acc.max(self.matrix[i][i].abs()));
+        T::epsilon() * T::from(self.row_size).unwrap() * largest
+    }
+
+    fn check_square(&self) -> Result<(), CholeskyError> {
+        if self.is_square() {
+            Ok(())
+        } else {
+            Err(CholeskyError::NotSquare {
+                rows: self.height(),
+                columns: self.row_size,
+            })
+        }
+    }
+}
+
+/// Subtracts `scale * row[i] * row` from every row `i` below `pivot`.
+fn eliminate_below<T: SimdScalar + Float, B: Backend<T> + ?Sized>(
+    backend: &B,
+    row: &SimdVector<T>,
+    remaining: &mut [SimdVector<T>],
+    pivot: usize,
+    scale: T,
+) {
+    remaining
+        .par_iter_mut()
+        .zip(row.as_slice()[pivot + 1..].par_iter())
+        .for_each(|(i, &factor)| backend.axpy(-scale * factor, row, i));
+}
+
+impl<T: SimdScalar + Float> Cholesky<T> {
+    /// The lower triangular factor `L`.
+    pub fn l(&self) -> SimdMatrix<T> {
+        self.upper.transpose()
+    }
+
+    pub fn size(&self) -> usize {
+        self.upper.row_size
+    }
+
+    /// `ln(det A)`, which stays finite for determinants that would over or
+    /// underflow.
+    pub fn log_determinant(&self) -> T {
+        (0..self.size()).fold(T::ZERO, |acc, i| acc + self.upper.matrix[i][i].ln())
+            * (T::ONE + T::ONE)
+    }
+
+    /// Solves `Ax = rhs` through `Ly = rhs` and `Lᵀx = y`.
+    pub fn solve(&self, rhs: &SimdVector<T>) -> Result<SimdVector<T>, CholeskyError> {
+        if rhs.len() != self.size() {
+            return Err(MatrixOpError::DimensionMismatch {
+                lhs: self.upper.dimensions(),
+                rhs: (rhs.len(), 1),
+            }
+            .into());
+        }
+        let mut x = rhs.clone();
+        forward_substitute(&self.upper, &mut x, |i| self.upper.matrix[i][i]);
+        back_substitute(&self.upper, &mut x, |i| self.upper.matrix[i][i]);
+        Ok(x)
+    }
+
+    /// Solves `AX = rhs` for every column of `rhs` at once, with one `axpy`
+    /// across all of the right hand sides per step.
+    pub fn solve_matrix(&self, rhs: &SimdMatrix<T>) -> Result<SimdMatrix<T>, CholeskyError> {
+        if rhs.height() != self.size() {
+            return Err(MatrixOpError::DimensionMismatch {
+                lhs: self.upper.dimensions(),
+                rhs: rhs.dimensions(),
+            }
+            .into());
+        }
+        let mut x = rhs.clone();
+        for k in 0..self.size() {
+            let row = &self.upper.matrix[k];
+            let (done, remaining) = x.matrix.split_at_mut(k + 1);
+            done[k] /= row[k];
+            for (i, factor) in remaining.iter_mut().zip(&row.as_slice()[k + 1..]) {
+                i.axpy(-*factor, &done[k]);
+            }
+        }
+        for k in (0..self.size()).rev() {
+            let row = &self.upper.matrix[k];
+            let (remaining, solved) = x.matrix.split_at_mut(k + 1);
+            for (i, factor) in solved.iter().zip(&row.as_slice()[k + 1..]) {
+                remaining[k].axpy(-*factor, i);
+            }
+            remaining[k] /= row[k];
+        }
+        Ok(x)
+    }
+
+    pub fn inverse(&self) -> SimdMatrix<T> {
+        self.solve_matrix(&SimdMatrix::identity(self.size()))
+            .expect("the identity has as many rows as the factorization")
+    }
+}
+
+impl<T: SimdScalar + Float> LDLT<T> {
+    /// The unit lower triangular factor `L`.
+    pub fn l(&self) -> SimdMatrix<T> {
+        self.upper.transpose()
+    }
+
+    /// The diagonal of `D`.
+    pub fn d(&self) -> &SimdVector<T> {
+        &self.diagonal
+    }
+
+    pub fn size(&self) -> usize {
+        self.upper.row_size
+    }
+
+    /// Solves `Ax = rhs`. Components along a zero pivot of `D` are set to
+    /// zero, which solves any consistent singular system.
+    pub fn solve(&self, rhs: &SimdVector<T>) -> Result<SimdVector<T>, CholeskyError> {
+        if rhs.len() != self.size() {
+            return Err(MatrixOpError::DimensionMismatch {
+                lhs: self.upper.dimensions(),
+                rhs: (rhs.len(), 1),
+            }
+            .into());
+        }
+        let mut x = rhs.clone();
+        forward_substitute(&self.upper, &mut x, |_| T::ONE);
+        for i in 0..self.size() {
+            let pivot = self.diagonal[i];
+            x[i] = if pivot == T::ZERO {
+                T::ZERO
+            } else {
+                x[i] / pivot
+            };
+        }
+        back_substitute(&self.upper, &mut x, |_| T::ONE);
+        Ok(x)
+    }
+}
+
+/// Solves `Uᵀy = x` in place, eliminating with one `axpy` per row of `U`.
+fn forward_substitute<T: SimdScalar + Float>(
+    upper: &SimdMatrix<T>,
+    x: &mut SimdVector<T>,
+    diagonal: impl Fn(usize) -> T,
+) {
+    for (k, row) in upper.matrix.iter().enumerate() {
+        let value = x[k] / diagonal(k);
+        x.axpy(-value, row);
+        x[k] = value;
+    }
+}
+
+/// Solves `Uy = x` in place with one SIMD dot product per row, relying on the
+/// zeros below the diagonal of `U`.
+fn back_substitute<T: SimdScalar + Float>(
+    upper: &SimdMatrix<T>,
+    x: &mut SimdVector<T>,
+    diagonal: impl Fn(usize) -> T,
+) {
+    for (k, row) in upper.matrix.iter().enumerate().rev() {
+        let value = x[k];
+        x[k] = T::ZERO;
+        x[k] = (value - row.checked_dot(x).unwrap()) / diagonal(k);
+    }
+}
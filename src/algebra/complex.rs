@@ -0,0 +1,117 @@
+use std::fmt::Debug;
+
+use num::{Complex, Float};
+
+use crate::structures::matrix_simd::MatrixOpError;
+
+/// Counterpart of [`Vector`](super::vector::Vector) for vectors over
+/// `Complex<Scalar>`. The inner product conjugates its left operand, so
+/// `dot(x, x)` is real and `magnitude` is the usual Euclidean norm.
+pub trait ComplexVector<Scalar: Float = f32>
+where
+    Self: Sized + Clone,
+    Scalar: Debug + std::fmt::Display,
+{
+    fn scale(self, scalar: Complex<Scalar>) -> Self;
+    fn add(self, rhs: Self) -> Self;
+    fn elem_mul(self, rhs: Self) -> Self;
+    fn conjugate(self) -> Self;
+
+    fn from_vec(input: Vec<Complex<Scalar>>) -> Self;
+    fn to_vec(&self) -> Vec<Complex<Scalar>>;
+
+    fn partial_sum(&self) -> Complex<Scalar>;
+
+    fn magnitude(&self) -> Scalar {
+        self.clone().dot(self.clone()).re.sqrt()
+    }
+
+    /// Hermitian inner product `Σ conj(self_i) * rhs_i`.
+    fn dot(self, rhs: Self) -> Complex<Scalar> {
+        self.conjugate().elem_mul(rhs).partial_sum()
+    }
+}
+
+/// Counterpart of [`Matrix`](super::vector::Matrix) for matrices over
+/// `Complex<Scalar>`, with the conjugate transpose alongside the plain one.
+pub trait ComplexMatrix<Scalar: Float = f32>: Sized
+where
+    Scalar: Debug + std::fmt::Display,
+{
+    type Line: ComplexVector<Scalar> + Debug;
+    type LineIter<'a>: Iterator<Item = Self::Line>
+    where
+        Self: 'a;
+    fn from_vectors(input: Vec<Self::Line>) -> Option<Self>;
+
+    fn dimensions(&self) -> (usize, usize);
+    fn row(&self, index: usize) -> Option<Self::Line>;
+    fn column(&self, index: usize) -> Option<Self::Line>;
+
+    fn row_iter<'a>(&'a self) -> Self::LineIter<'a>;
+    fn col_iter<'a>(&'a self) -> Self::LineIter<'a>;
+
+    fn transpose(&self) -> Self {
+        Self::from_vectors(self.col_iter().collect()).unwrap()
+    }
+
+    fn conjugate(&self) -> Self {
+        Self::from_vectors(self.row_iter().map(|i| i.conjugate()).collect()).unwrap()
+    }
+
+    /// The Hermitian adjoint `Aᴴ`.
+    fn conjugate_transpose(&self) -> Self {
+        Self::from_vectors(self.col_iter().map(|i| i.conjugate()).collect()).unwrap()
+    }
+
+    fn scale(&self, rhs: Complex<Scalar>) -> Self {
+        Self::from_vectors(self.row_iter().map(|i| i.scale(rhs)).collect()).unwrap()
+    }
+
+    fn add(self, rhs: Self) -> Option<Self> {
+        if self.dimensions() != rhs.dimensions() {
+            return None;
+        }
+        Self::from_vectors(
+            self.row_iter()
+                .zip(rhs.row_iter())
+                .map(|(i, j)| i.add(j))
+                .collect(),
+        )
+    }
+
+    // Products pair rows with columns without conjugating either, unlike
+    // `ComplexVector::dot`.
+    fn mul(self, rhs: Self) -> Option<Self> {
+        if self.dimensions().1 != rhs.dimensions().0 {
+            return None;
+        }
+        Self::from_vectors(
+            self.row_iter()
+                .map(|i| {
+                    Self::Line::from_vec(
+                        rhs.col_iter()
+                            .map(|j| j.elem_mul(i.clone()).partial_sum())
+                            .collect(),
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    /// Matrix-vector product `self * v`.
+    fn mul_vec(&self, v: &Self::Line) -> Result<Self::Line, MatrixOpError> {
+        let length = v.to_vec().len();
+        if self.dimensions().1 != length {
+            return Err(MatrixOpError::DimensionMismatch {
+                lhs: self.dimensions(),
+                rhs: (length, 1),
+            });
+        }
+        Ok(Self::Line::from_vec(
+            self.row_iter()
+                .map(|i| i.elem_mul(v.clone()).partial_sum())
+                .collect(),
+        ))
+    }
+}
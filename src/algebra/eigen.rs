@@ -0,0 +1,661 @@
+use std::cmp::Ordering;
+
+use num::{Complex, Float};
+use rayon::prelude::*;
+use thiserror::Error;
+
+use crate::{
+    backend,
+    structures::{matrix_simd::SimdMatrix, scalar::SimdScalar, vector_simd::SimdVector},
+};
+
+use super::qr::reflect_rows;
+
+/// Sweeps over every off-diagonal pair before a Jacobi method gives up.
+/// Cyclic Jacobi converges quadratically, so well conditioned input needs
+/// well under ten.
+pub(crate) const MAX_SWEEPS: usize = 50;
+
+/// Eigenpairs of a symmetric matrix from `SimdMatrix::symmetric_eigen`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SymmetricEigen<T: SimdScalar + Float = f32> {
+    /// Sorted by decreasing magnitude.
+    pub eigenvalues: SimdVector<T>,
+    /// Column `i` is the unit eigenvector for `eigenvalues[i]`.
+    pub eigenvectors: SimdMatrix<T>,
+}
+
+/// Eigenpairs of a general real matrix from `SimdMatrix::eigen`. Complex
+/// eigenvalues come in conjugate pairs, as do their eigenvectors.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeneralEigen<T: SimdScalar + Float = f32> {
+    /// Sorted by decreasing magnitude, with the positive imaginary half of a
+    /// conjugate pair first.
+    pub eigenvalues: Vec<Complex<T>>,
+    /// `eigenvectors[i]` is the unit eigenvector for `eigenvalues[i]`.
+    pub eigenvectors: Vec<Vec<Complex<T>>>,
+}
+
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+pub enum EigenError {
+    #[error(
+        "Eigenvalues are only defined for square matrices, but was given a {rows}x{columns} one"
+    )]
+    NotSquare { rows: usize, columns: usize },
+    #[error("Eigenvalue iteration did not converge within {iterations} iterations")]
+    NoConvergence { iterations: usize },
+}
+
+impl<T: SimdScalar + Float> SimdMatrix<T> {
+    /// Eigenvalues and eigenvectors of a symmetric matrix by cyclic Jacobi
+    /// rotations, which are accurate even for tiny eigenvalues.
+    ///
+    /// Each rotation combines two rows with SIMD `axpy`s and then fixes up the
+    /// two matching columns. The eigenvectors are accumulated as the rows of
+    /// `Vᵀ` so they are rotated the same way. Only the upper triangle is
+    /// read: symmetry is assumed, not checked.
+    pub fn symmetric_eigen(&self) -> Result<SymmetricEigen<T>, EigenError> {
+        if !self.is_square() {
+            return Err(EigenError::NotSquare {
+                rows: self.height(),
+                columns: self.row_size,
+            });
+        }
+        let size = self.row_size;
+        let mut matrix = SimdMatrix::from_fn(size, size, |i, j| self.matrix[i.min(j)][i.max(j)]);
+        let mut vectors = SimdMatrix::identity(size);
+        let tolerance = T::epsilon() * frobenius_norm(&matrix);
+
+        let mut converged = false;
+        for _ in 0..MAX_SWEEPS {
+            let off_diagonal = (0..size)
+                .flat_map(|p| (p + 1..size).map(move |q| (p, q)))
+                .fold(T::ZERO, |acc, (p, q)| acc + matrix.matrix[p][q].powi(2));
+            if off_diagonal.sqrt() <= tolerance {
+                converged = true;
+                break;
+            }
+            for p in 0..size {
+                for q in p + 1..size {
+                    let coupling = matrix.matrix[p][q];
+                    if coupling == T::ZERO {
+                        continue;
+                    }
+                    let theta = (matrix.matrix[q][q] - matrix.matrix[p][p])
+                        / ((T::ONE + T::ONE) * coupling);
+                    let tangent = theta.signum() / (theta.abs() + theta.hypot(T::ONE));
+                    let cosine = T::ONE / tangent.hypot(T::ONE);
+                    let sine = tangent * cosine;
+
+                    rotate_rows(&mut matrix, p, q, cosine, sine);
+                    for row in &mut matrix.matrix {
+                        let (a, b) = (row[p], row[q]);
+                        row[p] = cosine * a - sine * b;
+                        row[q] = sine * a + cosine * b;
+                    }
+                    matrix.matrix[p][q] = T::ZERO;
+                    matrix.matrix[q][p] = T::ZERO;
+                    rotate_rows(&mut vectors, p, q, cosine, sine);
+                }
+            }
+        }
+        if !converged {
+            return Err(EigenError::NoConvergence {
+                iterations: MAX_SWEEPS,
+            });
+        }
+
+        let mut order: Vec<usize> = (0..size).collect();
+        order.sort_by(|&i, &j| {
+            let (a, b) = (matrix.matrix[i][i].abs(), matrix.matrix[j][j].abs());
+            b.partial_cmp(&a).unwrap_or(Ordering::Equal)
+        });
+        let eigenvalues =
+            SimdVector::from_vector(order.iter().map(|&i| matrix.matrix[i][i]).collect());
+        let eigenvectors = SimdMatrix {
+            matrix: order.iter().map(|&i| vectors.matrix[i].clone()).collect(),
+            row_size: size,
+        }
+        .transpose();
+        Ok(SymmetricEigen {
+            eigenvalues,
+            eigenvectors,
+        })
+    }
+}
+
+impl<T: SimdScalar + Float> SimdMatrix<T> {
+    /// Eigenvalues of a general square matrix, sorted by decreasing
+    /// magnitude. See `eigen` for the method.
+    pub fn eigenvalues(&self) -> Result<Vec<Complex<T>>, EigenError> {
+        let (mut hessenberg, _) = self.hessenberg(false)?;
+        let (real, imaginary) = francis_qr(&mut hessenberg, None)?;
+        let mut eigenvalues: Vec<Complex<T>> = real
+            .into_iter()
+            .zip(imaginary)
+            .map(|(re, im)| Complex::new(re, im))
+            .collect();
+        eigenvalues.sort_by(|a, b| b.norm().partial_cmp(&a.norm()).unwrap_or(Ordering::Equal));
+        Ok(eigenvalues)
+    }
+
+    /// Eigenvalues and eigenvectors of a general square matrix.
+    ///
+    /// The matrix is first reduced to upper Hessenberg form with Householder
+    /// reflectors applied as SIMD row updates. Francis double shift QR steps
+    /// then take it to real Schur form, where the eigenvectors follow by back
+    /// substitution. The iteration is the EISPACK `hqr2` routine, and gives
+    /// up after `30 * max(10, n)` steps as LAPACK does.
+    pub fn eigen(&self) -> Result<GeneralEigen<T>, EigenError> {
+        let size = self.row_size;
+        let (mut hessenberg, transform) = self.hessenberg(true)?;
+        let mut schur_vectors = transform.to_vector();
+        let (real, imaginary) = francis_qr(&mut hessenberg, Some(&mut schur_vectors))?;
+
+        // Back substitution left the eigenvectors of the Schur form in the
+        // upper triangle of `hessenberg`; map them back to the original basis.
+        let triangular =
+            SimdMatrix::from_fn(
+                size,
+                size,
+                |i, j| {
+                    if i <= j {
+                        hessenberg[i][j]
+                    } else {
+                        T::ZERO
+                    }
+                },
+            );
+        let vectors = (&SimdMatrix::from(schur_vectors).unwrap() * &triangular).transpose();
+
+        let mut pairs: Vec<(Complex<T>, Vec<Complex<T>>)> = (0..size)
+            .map(|j| {
+                let eigenvalue = Complex::new(real[j], imaginary[j]);
+                let (re, im) = match imaginary[j].partial_cmp(&T::ZERO) {
+                    Some(Ordering::Greater) => (&vectors.matrix[j], Some(&vectors.matrix[j + 1])),
+                    Some(Ordering::Less) => (&vectors.matrix[j - 1], Some(&vectors.matrix[j])),
+                    _ => (&vectors.matrix[j], None),
+                };
+                let sign = if imaginary[j] < T::ZERO {
+                    -T::ONE
+                } else {
+                    T::ONE
+                };
+                let vector: Vec<Complex<T>> = match im {
+                    Some(im) => re
+                        .iter()
+                        .zip(im.iter())
+                        .map(|(a, b)| Complex::new(a, sign * b))
+                        .collect(),
+                    None => re.iter().map(|a| Complex::new(a, T::ZERO)).collect(),
+                };
+                let norm = vector
+                    .iter()
+                    .fold(T::ZERO, |acc, i| acc + i.norm_sqr())
+                    .sqrt();
+                (eigenvalue, vector.into_iter().map(|i| i / norm).collect())
+            })
+            .collect();
+        pairs.sort_by(|a, b| {
+            b.0.norm()
+                .partial_cmp(&a.0.norm())
+                .unwrap_or(Ordering::Equal)
+        });
+        let (eigenvalues, eigenvectors) = pairs.into_iter().unzip();
+        Ok(GeneralEigen {
+            eigenvalues,
+            eigenvectors,
+        })
+    }
+
+    /// Reduces the matrix to upper Hessenberg form `QᵀAQ` by Householder
+    /// reflectors, returning it as rows along with `Q` when `accumulate` is
+    /// set. Each reflector is applied from the left as in `qr`, and from the
+    /// right as a SIMD dot product and `axpy` on every row.
+    fn hessenberg(&self, accumulate: bool) -> Result<(Vec<Vec<T>>, SimdMatrix<T>), EigenError> {
+        if !self.is_square() {
+            return Err(EigenError::NotSquare {
+                rows: self.height(),
+                columns: self.row_size,
+            });
+        }
+        let size = self.row_size;
+        let mut matrix = self.clone();
+        let mut transform = if accumulate {
+            SimdMatrix::identity(size)
+        } else {
+            SimdMatrix::zeros(0, size)
+        };
+        for k in 0..size.saturating_sub(2) {
+            let mut reflector = SimdVector::zeros(size);
+            for i in k + 1..size {
+                reflector[i] = matrix.matrix[i][k];
+            }
+            let norm = reflector.checked_dot(&reflector).unwrap().sqrt();
+            if norm == T::ZERO {
+                continue;
+            }
+            let alpha = if reflector[k + 1] > T::ZERO {
+                -norm
+            } else {
+                norm
+            };
+            reflector[k + 1] = reflector[k + 1] - alpha;
+            let tau = (T::ONE + T::ONE) / reflector.checked_dot(&reflector).unwrap();
+
+            reflect_rows(&mut matrix, &reflector, tau, k + 1);
+            reflect_columns(&mut matrix, &reflector, tau);
+            reflect_columns(&mut transform, &reflector, tau);
+            matrix.matrix[k + 1][k] = alpha;
+            for row in &mut matrix.matrix[k + 2..] {
+                row[k] = T::ZERO;
+            }
+        }
+        Ok((matrix.to_vector(), transform))
+    }
+}
+
+/// `matrix = matrix * (I - tau * v * vᵀ)`, one dot product and `axpy` per row.
+fn reflect_columns<T: SimdScalar + Float>(
+    matrix: &mut SimdMatrix<T>,
+    reflector: &SimdVector<T>,
+    tau: T,
+) {
+    backend::with_current(|backend| {
+        matrix.matrix.par_iter_mut().for_each(|row| {
+            let projection = backend.dot(row, reflector);
+            backend.axpy(-tau * projection, reflector, row);
+        })
+    });
+}
+
+/// Francis double shift QR iteration on the upper Hessenberg `h`, ported
+/// from the EISPACK `hqr2` routine by way of JAMA. Returns the real and
+/// imaginary parts of the eigenvalues, with each conjugate pair stored as
+/// positive then negative imaginary part.
+///
+/// When `vectors` holds the Hessenberg transform, the Schur vectors are
+/// accumulated into it and `h` is overwritten with the eigenvectors of the
+/// Schur form, a complex pair `j, j + 1` as the real and imaginary parts in
+/// columns `j` and `j + 1`.
+fn francis_qr<T: SimdScalar + Float>(
+    h: &mut [Vec<T>],
+    mut vectors: Option<&mut Vec<Vec<T>>>,
+) -> Result<(Vec<T>, Vec<T>), EigenError> {
+    let size = h.len();
+    let two = T::ONE + T::ONE;
+    let eps = T::epsilon();
+    let constant = |value: f64| T::from(value).unwrap();
+    let mut real = vec![T::ZERO; size];
+    let mut imaginary = vec![T::ZERO; size];
+
+    let norm = h.iter().enumerate().fold(T::ZERO, |acc, (i, row)| {
+        row[i.saturating_sub(1)..]
+            .iter()
+            .fold(acc, |acc, j| acc + j.abs())
+    });
+    let limit = 30 * size.max(10);
+    let mut iterations = 0;
+    let mut since_deflation = 0;
+    let mut exceptional_shift = T::ZERO;
+    let mut remaining = size;
+    while remaining > 0 {
+        let n = remaining - 1;
+
+        // Look for a single small subdiagonal element.
+        let mut l = n;
+        while l > 0 {
+            let mut s = h[l - 1][l - 1].abs() + h[l][l].abs();
+            if s == T::ZERO {
+                s = norm;
+            }
+            // `<=` so that an all zero matrix, where `s` is zero, deflates.
+            if h[l][l - 1].abs() <= eps * s {
+                break;
+            }
+            l -= 1;
+        }
+
+        if l == n {
+            // One root found.
+            h[n][n] = h[n][n] + exceptional_shift;
+            real[n] = h[n][n];
+            imaginary[n] = T::ZERO;
+            remaining -= 1;
+            since_deflation = 0;
+        } else if l + 1 == n {
+            // Two roots found.
+            let w = h[n][n - 1] * h[n - 1][n];
+            let p = (h[n - 1][n - 1] - h[n][n]) / two;
+            let q = p * p + w;
+            let mut z = q.abs().sqrt();
+            h[n][n] = h[n][n] + exceptional_shift;
+            h[n - 1][n - 1] = h[n - 1][n - 1] + exceptional_shift;
+            let x = h[n][n];
+
+            if q >= T::ZERO {
+                // A real pair, split off with a rotation.
+                z = if p >= T::ZERO { p + z } else { p - z };
+                real[n - 1] = x + z;
+                real[n] = if z != T::ZERO { x - w / z } else { x + z };
+                let x = h[n][n - 1];
+                let s = x.abs() + z.abs();
+                let r = (x / s).hypot(z / s);
+                let (p, q) = (x / s / r, z / s / r);
+                let rotate = |a: &mut T, b: &mut T| {
+                    let z = *a;
+                    *a = q * z + p * *b;
+                    *b = q * *b - p * z;
+                };
+                let (upper, lower) = h.split_at_mut(n);
+                upper[n - 1][n - 1..]
+                    .iter_mut()
+                    .zip(&mut lower[0][n - 1..])
+                    .for_each(|(a, b)| rotate(a, b));
+                for row in h[..=n].iter_mut() {
+                    let (a, b) = row.split_at_mut(n);
+                    rotate(&mut a[n - 1], &mut b[0]);
+                }
+                if let Some(vectors) = vectors.as_deref_mut() {
+                    for row in vectors.iter_mut() {
+                        let (a, b) = row.split_at_mut(n);
+                        rotate(&mut a[n - 1], &mut b[0]);
+                    }
+                }
+            } else {
+                real[n - 1] = x + p;
+                real[n] = x + p;
+                imaginary[n - 1] = z;
+                imaginary[n] = -z;
+            }
+            remaining -= 2;
+            since_deflation = 0;
+        } else {
+            iterations += 1;
+            if iterations > limit {
+                return Err(EigenError::NoConvergence { iterations: limit });
+            }
+
+            // Form the shift.
+            let mut x = h[n][n];
+            let mut y = h[n - 1][n - 1];
+            let mut w = h[n][n - 1] * h[n - 1][n];
+            if since_deflation == 10 {
+                // Wilkinson's ad hoc shift.
+                exceptional_shift = exceptional_shift + x;
+                shift_diagonal(&mut h[..=n], x);
+                let s = h[n][n - 1].abs() + h[n - 1][n - 2].abs();
+                x = constant(0.75) * s;
+                y = x;
+                w = constant(-0.4375) * s * s;
+            }
+            if since_deflation == 30 {
+                // MATLAB's ad hoc shift.
+                let s = (y - x) / two;
+                let s = s * s + w;
+                if s > T::ZERO {
+                    let s = if y < x { -s.sqrt() } else { s.sqrt() };
+                    let s = x - w / ((y - x) / two + s);
+                    shift_diagonal(&mut h[..=n], s);
+                    exceptional_shift = exceptional_shift + s;
+                    x = constant(0.964);
+                    y = x;
+                    w = x;
+                }
+            }
+            since_deflation += 1;
+
+            // Look for two consecutive small subdiagonal elements.
+            let mut m = n - 2;
+            let (mut p, mut q, mut r);
+            loop {
+                let z = h[m][m];
+                let (r_shift, s_shift) = (x - z, y - z);
+                p = (r_shift * s_shift - w) / h[m + 1][m] + h[m][m + 1];
+                q = h[m + 1][m + 1] - z - r_shift - s_shift;
+                r = h[m + 2][m + 1];
+                let s = p.abs() + q.abs() + r.abs();
+                p = p / s;
+                q = q / s;
+                r = r / s;
+                if m == l
+                    || h[m][m - 1].abs() * (q.abs() + r.abs())
+                        < eps
+                            * (p.abs() * (h[m - 1][m - 1].abs() + z.abs() + h[m + 1][m + 1].abs()))
+                {
+                    break;
+                }
+                m -= 1;
+            }
+            for i in m + 2..=n {
+                h[i][i - 2] = T::ZERO;
+                if i > m + 2 {
+                    h[i][i - 3] = T::ZERO;
+                }
+            }
+
+            // Double QR step on rows l..=n and columns m..=n.
+            for k in m..n {
+                let not_last = k != n - 1;
+                let mut scale = T::ZERO;
+                if k != m {
+                    p = h[k][k - 1];
+                    q = h[k + 1][k - 1];
+                    r = if not_last { h[k + 2][k - 1] } else { T::ZERO };
+                    scale = p.abs() + q.abs() + r.abs();
+                    if scale == T::ZERO {
+                        continue;
+                    }
+                    p = p / scale;
+                    q = q / scale;
+                    r = r / scale;
+                }
+                let mut s = (p * p + q * q + r * r).sqrt();
+                if p < T::ZERO {
+                    s = -s;
+                }
+                if s == T::ZERO {
+                    continue;
+                }
+                if k != m {
+                    h[k][k - 1] = -s * scale;
+                } else if l != m {
+                    h[k][k - 1] = -h[k][k - 1];
+                }
+                p = p + s;
+                let (x, y, z) = (p / s, q / s, r / s);
+                q = q / p;
+                r = r / p;
+
+                let (upper, lower) = h.split_at_mut(k + 1);
+                let (middle, lower) = lower.split_at_mut(1);
+                let (row_k, row_k1) = (&mut upper[k], &mut middle[0]);
+                let mut row_k2 = lower.first_mut().filter(|_| not_last);
+                for j in k..size {
+                    let mut p = row_k[j] + q * row_k1[j];
+                    if let Some(row_k2) = row_k2.as_deref_mut() {
+                        p = p + r * row_k2[j];
+                        row_k2[j] = row_k2[j] - p * z;
+                    }
+                    row_k[j] = row_k[j] - p * x;
+                    row_k1[j] = row_k1[j] - p * y;
+                }
+                let apply_columns = |row: &mut Vec<T>| {
+                    let mut p = x * row[k] + y * row[k + 1];
+                    if not_last {
+                        p = p + z * row[k + 2];
+                        row[k + 2] = row[k + 2] - p * r;
+                    }
+                    row[k] = row[k] - p;
+                    row[k + 1] = row[k + 1] - p * q;
+                };
+                h[..=n.min(k + 3)].iter_mut().for_each(apply_columns);
+                if let Some(vectors) = vectors.as_deref_mut() {
+                    vectors.iter_mut().for_each(apply_columns);
+                }
+            }
+        }
+    }
+
+    if vectors.is_some() {
+        if norm == T::ZERO {
+            // A zero Schur form has the unit vectors as eigenvectors, so the
+            // Schur vectors themselves are the result, as in `hqr2`.
+            for (i, row) in h.iter_mut().enumerate() {
+                row[i] = T::ONE;
+            }
+        } else {
+            back_substitute_schur(h, &real, &imaginary, norm);
+        }
+    }
+    Ok((real, imaginary))
+}
+
+fn shift_diagonal<T: SimdScalar + Float>(rows: &mut [Vec<T>], shift: T) {
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[i] = row[i] - shift;
+    }
+}
+
+/// Overwrites the upper triangle of the real Schur form `h` with its
+/// eigenvectors, solving each triangular system from the bottom up.
+fn back_substitute_schur<T: SimdScalar + Float>(
+    h: &mut [Vec<T>],
+    real: &[T],
+    imaginary: &[T],
+    norm: T,
+) {
+    let size = h.len();
+    let eps = T::epsilon();
+    let divide = |a: T, b: T, c: T, d: T| Complex::new(a, b).fdiv(Complex::new(c, d));
+    for n in (0..size).rev() {
+        let (p, q) = (real[n], imaginary[n]);
+        if q == T::ZERO {
+            // Real vector.
+            let mut l = n;
+            h[n][n] = T::ONE;
+            let (mut z, mut s) = (T::ZERO, T::ZERO);
+            for i in (0..n).rev() {
+                let w = h[i][i] - p;
+                let r = (l..=n).fold(T::ZERO, |acc, j| acc + h[i][j] * h[j][n]);
+                if imaginary[i] < T::ZERO {
+                    z = w;
+                    s = r;
+                    continue;
+                }
+                l = i;
+                if imaginary[i] == T::ZERO {
+                    h[i][n] = if w != T::ZERO {
+                        -r / w
+                    } else {
+                        -r / (eps * norm)
+                    };
+                } else {
+                    let x = h[i][i + 1];
+                    let y = h[i + 1][i];
+                    let q = (real[i] - p) * (real[i] - p) + imaginary[i] * imaginary[i];
+                    let t = (x * s - z * r) / q;
+                    h[i][n] = t;
+                    h[i + 1][n] = if x.abs() > z.abs() {
+                        (-r - w * t) / x
+                    } else {
+                        (-s - y * t) / z
+                    };
+                }
+                let t = h[i][n].abs();
+                if eps * t * t > T::ONE {
+                    for row in &mut h[i..=n] {
+                        row[n] = row[n] / t;
+                    }
+                }
+            }
+        } else if q < T::ZERO {
+            // Complex vector, with the real part in column n - 1 and the
+            // imaginary part in column n.
+            let mut l = n - 1;
+            if h[n][n - 1].abs() > h[n - 1][n].abs() {
+                h[n - 1][n - 1] = q / h[n][n - 1];
+                h[n - 1][n] = -(h[n][n] - p) / h[n][n - 1];
+            } else {
+                let c = divide(T::ZERO, -h[n - 1][n], h[n - 1][n - 1] - p, q);
+                h[n - 1][n - 1] = c.re;
+                h[n - 1][n] = c.im;
+            }
+            h[n][n - 1] = T::ZERO;
+            h[n][n] = T::ONE;
+            let (mut z, mut r, mut s) = (T::ZERO, T::ZERO, T::ZERO);
+            for i in (0..n - 1).rev() {
+                let (ra, sa) = (l..=n).fold((T::ZERO, T::ZERO), |(ra, sa), j| {
+                    (ra + h[i][j] * h[j][n - 1], sa + h[i][j] * h[j][n])
+                });
+                let w = h[i][i] - p;
+                if imaginary[i] < T::ZERO {
+                    z = w;
+                    r = ra;
+                    s = sa;
+                    continue;
+                }
+                l = i;
+                if imaginary[i] == T::ZERO {
+                    let c = divide(-ra, -sa, w, q);
+                    h[i][n - 1] = c.re;
+                    h[i][n] = c.im;
+                } else {
+                    let x = h[i][i + 1];
+                    let y = h[i + 1][i];
+                    let mut vr =
+                        (real[i] - p) * (real[i] - p) + imaginary[i] * imaginary[i] - q * q;
+                    let vi = (real[i] - p) * (T::ONE + T::ONE) * q;
+                    if vr == T::ZERO && vi == T::ZERO {
+                        vr = eps * norm * (w.abs() + q.abs() + x.abs() + y.abs() + z.abs());
+                    }
+                    let c = divide(x * r - z * ra + q * sa, x * s - z * sa - q * ra, vr, vi);
+                    h[i][n - 1] = c.re;
+                    h[i][n] = c.im;
+                    if x.abs() > z.abs() + q.abs() {
+                        h[i + 1][n - 1] = (-ra - w * h[i][n - 1] + q * h[i][n]) / x;
+                        h[i + 1][n] = (-sa - w * h[i][n] - q * h[i][n - 1]) / x;
+                    } else {
+                        let c = divide(-r - y * h[i][n - 1], -s - y * h[i][n], z, q);
+                        h[i + 1][n - 1] = c.re;
+                        h[i + 1][n] = c.im;
+                    }
+                }
+                let t = h[i][n - 1].abs().max(h[i][n].abs());
+                if eps * t * t > T::ONE {
+                    for row in &mut h[i..=n] {
+                        row[n - 1] = row[n - 1] / t;
+                        row[n] = row[n] / t;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Replaces rows `p` and `q` with `c * p - s * q` and `s * p + c * q`, i.e.
+/// left multiplies by the transpose of a Givens rotation in the `(p, q)`
+/// plane. Requires `p < q`.
+pub(crate) fn rotate_rows<T: SimdScalar + Float>(
+    matrix: &mut SimdMatrix<T>,
+    p: usize,
+    q: usize,
+    cosine: T,
+    sine: T,
+) {
+    let (upper, lower) = matrix.matrix.split_at_mut(q);
+    let (row_p, row_q) = (&mut upper[p], &mut lower[0]);
+    let original_p = row_p.clone();
+    *row_p *= cosine;
+    row_p.axpy(-sine, row_q);
+    *row_q *= cosine;
+    row_q.axpy(sine, &original_p);
+}
+
+fn frobenius_norm<T: SimdScalar + Float>(matrix: &SimdMatrix<T>) -> T {
+    matrix
+        .matrix
+        .iter()
+        .fold(T::ZERO, |acc, i| acc + i.checked_dot(i).unwrap())
+        .sqrt()
+}
@@ -0,0 +1,223 @@
+use num::Float;
+use rayon::prelude::*;
+use thiserror::Error;
+
+use crate::{
+    backend::{self, Backend},
+    structures::{
+        matrix_simd::{MatrixOpError, SimdMatrix},
+        scalar::SimdScalar,
+        vector_simd::SimdVector,
+    },
+};
+
+/// `PA = LU` factorization of a square matrix, computed with partial
+/// pivoting by `SimdMatrix::lu`.
+///
+/// `L` is unit lower triangular and is stored without its diagonal, `U` is
+/// upper triangular. Both keep exact zeros on the other side of the diagonal,
+/// which lets the triangular solves run as whole-row SIMD dot products and
+/// `axpy`s.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LU<T: SimdScalar + Float = f32> {
+    pub(crate) lower: SimdMatrix<T>,
+    pub(crate) upper: SimdMatrix<T>,
+    pub(crate) permutation: Vec<usize>,
+    pub(crate) swaps: usize,
+}
+
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+pub enum LuError {
+    #[error("LU decomposition needs a square matrix, but was given a {rows}x{columns} one")]
+    NotSquare { rows: usize, columns: usize },
+    #[error(
+        "Matrix is singular to working precision: no usable pivot was left for column {pivot}"
+    )]
+    Singular { pivot: usize },
+    #[error(transparent)]
+    DimensionMismatch(#[from] MatrixOpError),
+}
+
+impl<T: SimdScalar + Float> SimdMatrix<T> {
+    /// Factors the matrix as `PA = LU`, picking the largest remaining element
+    /// of each column as the pivot. Rows below the pivot are eliminated in
+    /// parallel with one SIMD `axpy` each.
+    ///
+    /// A pivot no larger than `n * epsilon * max|a_ij|` is treated as zero,
+    /// so singular and nearly singular matrices both return
+    /// `LuError::Singular`.
+    ///
+    /// Runs on the current backend, which is this SIMD elimination unless the
+    /// backend forwards it, e.g. to LAPACK.
+    pub fn lu(&self) -> Result<LU<T>, LuError> {
+        if !self.is_square() {
+            return Err(LuError::NotSquare {
+                rows: self.height(),
+                columns: self.row_size,
+            });
+        }
+        backend::with_current(|i| i.lu(self))
+    }
+
+    /// The SIMD factorization behind `lu`, for a square matrix, with the row
+    /// updates on `backend`.
+    pub(crate) fn simd_lu<B: Backend<T> + ?Sized>(&self, backend: &B) -> Result<LU<T>, LuError> {
+        let size = self.row_size;
+        let tolerance = self.singular_tolerance();
+
+        let mut upper = self.clone();
+        let mut lower = SimdMatrix::zeros(size, size);
+        let mut permutation: Vec<usize> = (0..size).collect();
+        let mut swaps = 0;
+        for k in 0..size {
+            let (pivot, pivot_value) = (k..size)
+                .map(|i| (i, upper.matrix[i][k].abs()))
+                .fold((k, T::ZERO), |acc, i| if i.1 > acc.1 { i } else { acc });
+            if pivot_value <= tolerance {
+                return Err(LuError::Singular { pivot: k });
+            }
+            if pivot != k {
+                upper.matrix.swap(pivot, k);
+                lower.matrix.swap(pivot, k);
+                permutation.swap(pivot, k);
+                swaps += 1;
+            }
+
+            let (done, remaining) = upper.matrix.split_at_mut(k + 1);
+            let pivot_row = &done[k];
+            remaining
+                .par_iter_mut()
+                .zip(lower.matrix[k + 1..].par_iter_mut())
+                .for_each(|(row, multipliers)| {
+                    let factor = row[k] / pivot_row[k];
+                    if factor != T::ZERO {
+                        backend.axpy(-factor, pivot_row, row);
+                        row[k] = T::ZERO;
+                        multipliers[k] = factor;
+                    }
+                });
+        }
+
+        Ok(LU {
+            lower,
+            upper,
+            permutation,
+            swaps,
+        })
+    }
+
+    /// `n * epsilon * max|a_ij|`, the largest pivot `lu` treats as zero.
+    pub(crate) fn singular_tolerance(&self) -> T {
+        let largest = self
+            .matrix
+            .iter()
+            .flat_map(SimdVector::iter)
+            .fold(T::ZERO, |acc, i| acc.max(i.abs()));
+        T::epsilon() * T::from(self.row_size).unwrap() * largest
+    }
+}
+
+impl<T: SimdScalar + Float> LU<T> {
+    /// The unit lower triangular factor, with its ones on the diagonal.
+    pub fn l(&self) -> SimdMatrix<T> {
+        let mut lower = self.lower.clone();
+        lower
+            .matrix
+            .iter_mut()
+            .enumerate()
+            .for_each(|(i, row)| row[i] = T::ONE);
+        lower
+    }
+
+    pub fn u(&self) -> &SimdMatrix<T> {
+        &self.upper
+    }
+
+    /// Row `i` of `PA` is row `permutation()[i]` of `A`.
+    pub fn permutation(&self) -> &[usize] {
+        &self.permutation
+    }
+
+    pub fn size(&self) -> usize {
+        self.upper.row_size
+    }
+
+    pub fn determinant(&self) -> T {
+        let product = self
+            .upper
+            .matrix
+            .iter()
+            .enumerate()
+            .fold(T::ONE, |acc, (i, row)| acc * row[i]);
+        if self.swaps.is_multiple_of(2) {
+            product
+        } else {
+            -product
+        }
+    }
+
+    /// Solves `Ax = rhs` by forward and back substitution.
+    pub fn solve(&self, rhs: &SimdVector<T>) -> Result<SimdVector<T>, LuError> {
+        if rhs.len() != self.size() {
+            return Err(MatrixOpError::DimensionMismatch {
+                lhs: self.upper.dimensions(),
+                rhs: (rhs.len(), 1),
+            }
+            .into());
+        }
+        let mut x = SimdVector::from_vector(self.permutation.iter().map(|&i| rhs[i]).collect());
+        // The unsolved entries of `x` only ever meet zeros of the triangular
+        // factor, so each step is a full-length dot product.
+        for i in 0..self.size() {
+            x[i] = x[i] - self.lower.matrix[i].checked_dot(&x).unwrap();
+        }
+        for i in (0..self.size()).rev() {
+            let value = x[i];
+            x[i] = T::ZERO;
+            let row = &self.upper.matrix[i];
+            x[i] = (value - row.checked_dot(&x).unwrap()) / row[i];
+        }
+        Ok(x)
+    }
+
+    /// Solves `AX = rhs` for every column of `rhs` at once. The substitutions
+    /// run over whole rows of `X`, so each step is an `axpy` across all of
+    /// the right hand sides.
+    pub fn solve_matrix(&self, rhs: &SimdMatrix<T>) -> Result<SimdMatrix<T>, LuError> {
+        if rhs.height() != self.size() {
+            return Err(MatrixOpError::DimensionMismatch {
+                lhs: self.upper.dimensions(),
+                rhs: rhs.dimensions(),
+            }
+            .into());
+        }
+        let mut x = SimdMatrix {
+            matrix: self
+                .permutation
+                .iter()
+                .map(|&i| rhs.matrix[i].clone())
+                .collect(),
+            row_size: rhs.row_size,
+        };
+        for i in 0..self.size() {
+            let (solved, remaining) = x.matrix.split_at_mut(i);
+            for (j, row) in solved.iter().enumerate() {
+                remaining[0].axpy(-self.lower.matrix[i][j], row);
+            }
+        }
+        for i in (0..self.size()).rev() {
+            let (remaining, solved) = x.matrix.split_at_mut(i + 1);
+            let row = &self.upper.matrix[i];
+            for (j, solved_row) in solved.iter().enumerate() {
+                remaining[i].axpy(-row[i + 1 + j], solved_row);
+            }
+            remaining[i] /= row[i];
+        }
+        Ok(x)
+    }
+
+    pub fn inverse(&self) -> SimdMatrix<T> {
+        self.solve_matrix(&SimdMatrix::identity(self.size()))
+            .expect("the identity has as many rows as the factorization")
+    }
+}
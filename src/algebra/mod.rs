@@ -1,4 +1,13 @@
+pub mod cholesky;
+pub mod complex;
+pub mod eigen;
+pub mod lu;
+pub mod preconditioners;
+pub mod qr;
 pub mod simd;
+pub mod solvers;
+pub mod sparse;
+pub mod svd;
 pub mod vector;
 #[cfg(feature = "async")]
 pub mod vector_async;
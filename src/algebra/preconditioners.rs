@@ -0,0 +1,344 @@
+use std::ops::Range;
+
+use num::Float;
+use thiserror::Error;
+
+use crate::structures::{
+    matrix_simd::SimdMatrix, scalar::SimdScalar, sparse::CsrMatrix, vector_simd::SimdVector,
+};
+
+/// An approximation `M⁻¹` of the inverse of a system matrix, applied to
+/// residuals by the solvers in [`solvers`](super::solvers) to cut down the
+/// number of iterations.
+///
+/// Also implemented for closures, so a one-off preconditioner needs no type
+/// of its own. Like the vector operators, `apply` panics when given a vector
+/// of the wrong length.
+pub trait Preconditioner<T: SimdScalar + Float = f32> {
+    fn apply(&self, residual: &SimdVector<T>) -> SimdVector<T>;
+}
+
+impl<T: SimdScalar + Float, F: Fn(&SimdVector<T>) -> SimdVector<T>> Preconditioner<T> for F {
+    fn apply(&self, residual: &SimdVector<T>) -> SimdVector<T> {
+        self(residual)
+    }
+}
+
+/// No preconditioning, `M = I`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Identity;
+
+impl<T: SimdScalar + Float> Preconditioner<T> for Identity {
+    fn apply(&self, residual: &SimdVector<T>) -> SimdVector<T> {
+        residual.clone()
+    }
+}
+
+/// `M = D`, the diagonal of the matrix, applied as one SIMD multiplication
+/// by the stored reciprocals.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Jacobi<T: SimdScalar + Float = f32> {
+    inverse_diagonal: SimdVector<T>,
+}
+
+/// Symmetric successive over-relaxation,
+/// `M = ω/(2 - ω) (D/ω + L) (D/ω)⁻¹ (D/ω + U)` for the strictly lower and
+/// upper triangles `L` and `U`. Symmetric positive definite for symmetric
+/// positive definite matrices and `0 < ω < 2`, so it suits conjugate
+/// gradient; `ω = 1` is symmetric Gauss-Seidel.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ssor<T: SimdScalar + Float = f32> {
+    matrix: CsrMatrix<T>,
+    scaled_diagonal: Vec<T>,
+    omega: T,
+}
+
+/// Zero fill-in incomplete Cholesky, `M = LLᵀ` with `L` restricted to the
+/// sparsity pattern of the lower triangle of the matrix. For a matrix with
+/// no zeros nothing is dropped and this is the exact Cholesky factorization.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IncompleteCholesky<T: SimdScalar + Float = f32> {
+    lower: CsrMatrix<T>,
+    upper: CsrMatrix<T>,
+}
+
+/// Zero fill-in incomplete LU, `M = LU` with unit lower triangular `L` and
+/// upper triangular `U` sharing the sparsity pattern of the matrix, stored
+/// together as one matrix.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IncompleteLU<T: SimdScalar + Float = f32> {
+    factors: CsrMatrix<T>,
+    diagonal: Vec<usize>,
+}
+
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+pub enum PreconditionerError {
+    #[error("Preconditioners need a square matrix, but were given a {rows}x{columns} one")]
+    NotSquare { rows: usize, columns: usize },
+    #[error("Pivot {pivot} is zero or missing from the sparsity pattern")]
+    ZeroPivot { pivot: usize },
+    #[error("Incomplete Cholesky factorization failed: pivot {pivot} is not positive")]
+    NotPositiveDefinite { pivot: usize },
+    #[error("The SSOR relaxation factor must lie strictly between 0 and 2")]
+    InvalidRelaxation,
+}
+
+impl<T: SimdScalar + Float> Jacobi<T> {
+    pub fn from_dense(matrix: &SimdMatrix<T>) -> Result<Jacobi<T>, PreconditionerError> {
+        check_square(matrix.dimensions())?;
+        Self::from_diagonal((0..matrix.height()).map(|i| matrix.matrix[i][i]))
+    }
+
+    pub fn from_csr(matrix: &CsrMatrix<T>) -> Result<Jacobi<T>, PreconditionerError> {
+        let (size, _) = check_square(matrix.dimensions())?;
+        Self::from_diagonal((0..size).map(|i| matrix.get(i, i).unwrap()))
+    }
+
+    fn from_diagonal(diagonal: impl Iterator<Item = T>) -> Result<Jacobi<T>, PreconditionerError> {
+        let inverse = diagonal
+            .enumerate()
+            .map(|(pivot, i)| {
+                if i == T::ZERO || i.is_nan() {
+                    Err(PreconditionerError::ZeroPivot { pivot })
+                } else {
+                    Ok(T::ONE / i)
+                }
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Jacobi {
+            inverse_diagonal: SimdVector::from_vector(inverse),
+        })
+    }
+}
+
+impl<T: SimdScalar + Float> Preconditioner<T> for Jacobi<T> {
+    fn apply(&self, residual: &SimdVector<T>) -> SimdVector<T> {
+        residual * &self.inverse_diagonal
+    }
+}
+
+impl<T: SimdScalar + Float> Ssor<T> {
+    pub fn from_dense(matrix: &SimdMatrix<T>, omega: T) -> Result<Ssor<T>, PreconditionerError> {
+        check_square(matrix.dimensions())?;
+        Self::from_csr(&CsrMatrix::from_dense(matrix), omega)
+    }
+
+    pub fn from_csr(matrix: &CsrMatrix<T>, omega: T) -> Result<Ssor<T>, PreconditionerError> {
+        let (size, _) = check_square(matrix.dimensions())?;
+        if !(omega > T::ZERO && omega < T::ONE + T::ONE) {
+            return Err(PreconditionerError::InvalidRelaxation);
+        }
+        let scaled_diagonal = (0..size)
+            .map(|pivot| match matrix.get(pivot, pivot).unwrap() {
+                i if i == T::ZERO || i.is_nan() => Err(PreconditionerError::ZeroPivot { pivot }),
+                i => Ok(i / omega),
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Ssor {
+            matrix: matrix.clone(),
+            scaled_diagonal,
+            omega,
+        })
+    }
+}
+
+impl<T: SimdScalar + Float> Preconditioner<T> for Ssor<T> {
+    fn apply(&self, residual: &SimdVector<T>) -> SimdVector<T> {
+        let mut x = residual.to_vector();
+        let diagonal = |i: usize| self.scaled_diagonal[i];
+        forward_substitute(&self.matrix, &mut x, diagonal);
+        for (i, d) in x.iter_mut().zip(&self.scaled_diagonal) {
+            *i = *i * *d;
+        }
+        back_substitute(&self.matrix, &mut x, diagonal);
+        SimdVector::from_vector(x) * ((T::ONE + T::ONE - self.omega) / self.omega)
+    }
+}
+
+impl<T: SimdScalar + Float> IncompleteCholesky<T> {
+    /// Factors the lower triangle of `matrix`, which is assumed symmetric.
+    pub fn from_dense(
+        matrix: &SimdMatrix<T>,
+    ) -> Result<IncompleteCholesky<T>, PreconditionerError> {
+        check_square(matrix.dimensions())?;
+        Self::from_csr(&CsrMatrix::from_dense(matrix))
+    }
+
+    /// Factors the lower triangle of `matrix`, which is assumed symmetric.
+    pub fn from_csr(matrix: &CsrMatrix<T>) -> Result<IncompleteCholesky<T>, PreconditionerError> {
+        let (size, _) = check_square(matrix.dimensions())?;
+        let mut lower = matrix.lower_triangle();
+        let (offsets, indices, values) = lower.parts_mut();
+        for i in 0..size {
+            let (start, end) = (offsets[i], offsets[i + 1]);
+            if start == end || indices[end - 1] != i {
+                return Err(PreconditionerError::ZeroPivot { pivot: i });
+            }
+            for position in start..end {
+                let k = indices[position];
+                // Row `k` without its diagonal, which is its last entry.
+                let row_k = offsets[k]..offsets[k + 1] - 1;
+                let value = values[position] - sparse_dot(indices, values, start..position, row_k);
+                values[position] = if k < i {
+                    value / values[offsets[k + 1] - 1]
+                } else if value > T::ZERO {
+                    value.sqrt()
+                } else {
+                    return Err(PreconditionerError::NotPositiveDefinite { pivot: i });
+                };
+            }
+        }
+        Ok(IncompleteCholesky {
+            upper: lower.transpose(),
+            lower,
+        })
+    }
+
+    /// The lower triangular factor `L`.
+    pub fn l(&self) -> &CsrMatrix<T> {
+        &self.lower
+    }
+}
+
+impl<T: SimdScalar + Float> Preconditioner<T> for IncompleteCholesky<T> {
+    fn apply(&self, residual: &SimdVector<T>) -> SimdVector<T> {
+        let mut x = residual.to_vector();
+        forward_substitute(&self.lower, &mut x, |i| {
+            *self.lower.row_entries(i).1.last().unwrap()
+        });
+        back_substitute(&self.upper, &mut x, |i| self.upper.row_entries(i).1[0]);
+        SimdVector::from_vector(x)
+    }
+}
+
+impl<T: SimdScalar + Float> IncompleteLU<T> {
+    pub fn from_dense(matrix: &SimdMatrix<T>) -> Result<IncompleteLU<T>, PreconditionerError> {
+        check_square(matrix.dimensions())?;
+        Self::from_csr(&CsrMatrix::from_dense(matrix))
+    }
+
+    /// Gaussian elimination in the IKJ order, row by row, dropping every
+    /// update that falls outside the sparsity pattern.
+    pub fn from_csr(matrix: &CsrMatrix<T>) -> Result<IncompleteLU<T>, PreconditionerError> {
+        let (size, _) = check_square(matrix.dimensions())?;
+        let mut factors = matrix.clone();
+        let (offsets, indices, values) = factors.parts_mut();
+        let diagonal = (0..size)
+            .map(|pivot| {
+                indices[offsets[pivot]..offsets[pivot + 1]]
+                    .binary_search(&pivot)
+                    .map(|i| offsets[pivot] + i)
+                    .map_err(|_| PreconditionerError::ZeroPivot { pivot })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for i in 0..size {
+            for position in offsets[i]..diagonal[i] {
+                let k = indices[position];
+                let factor = values[position] / values[diagonal[k]];
+                values[position] = factor;
+                // Subtract `factor` times row `k` of `U` where the patterns
+                // of rows `i` and `k` meet.
+                let (mut a, mut b) = (position + 1, diagonal[k] + 1);
+                while a < offsets[i + 1] && b < offsets[k + 1] {
+                    match indices[a].cmp(&indices[b]) {
+                        std::cmp::Ordering::Less => a += 1,
+                        std::cmp::Ordering::Greater => b += 1,
+                        std::cmp::Ordering::Equal => {
+                            values[a] = values[a] - factor * values[b];
+                            a += 1;
+                            b += 1;
+                        }
+                    }
+                }
+            }
+            let pivot = values[diagonal[i]];
+            if pivot == T::ZERO || !pivot.is_finite() {
+                return Err(PreconditionerError::ZeroPivot { pivot: i });
+            }
+        }
+        Ok(IncompleteLU { factors, diagonal })
+    }
+
+    /// `L` below the diagonal and `U` on and above it, with the unit diagonal
+    /// of `L` left implicit.
+    pub fn factors(&self) -> &CsrMatrix<T> {
+        &self.factors
+    }
+}
+
+impl<T: SimdScalar + Float> Preconditioner<T> for IncompleteLU<T> {
+    fn apply(&self, residual: &SimdVector<T>) -> SimdVector<T> {
+        let mut x = residual.to_vector();
+        forward_substitute(&self.factors, &mut x, |_| T::ONE);
+        let values = self.factors.values();
+        back_substitute(&self.factors, &mut x, |i| values[self.diagonal[i]]);
+        SimdVector::from_vector(x)
+    }
+}
+
+fn check_square(dimensions: (usize, usize)) -> Result<(usize, usize), PreconditionerError> {
+    match dimensions {
+        (rows, columns) if rows == columns => Ok(dimensions),
+        (rows, columns) => Err(PreconditionerError::NotSquare { rows, columns }),
+    }
+}
+
+/// `Σ values[a] * values[b]` over the positions of the two ranges whose
+/// indices match, both ranges holding sorted indices.
+fn sparse_dot<T: SimdScalar + Float>(
+    indices: &[usize],
+    values: &[T],
+    mut lhs: Range<usize>,
+    mut rhs: Range<usize>,
+) -> T {
+    let mut sum = T::ZERO;
+    while !lhs.is_empty() && !rhs.is_empty() {
+        match indices[lhs.start].cmp(&indices[rhs.start]) {
+            std::cmp::Ordering::Less => lhs.start += 1,
+            std::cmp::Ordering::Greater => rhs.start += 1,
+            std::cmp::Ordering::Equal => {
+                sum = sum + values[lhs.start] * values[rhs.start];
+                lhs.start += 1;
+                rhs.start += 1;
+            }
+        }
+    }
+    sum
+}
+
+/// Solves `(D + L)y = x` in place, with `L` the strictly lower triangle of
+/// `matrix` and `D` given by `diagonal`.
+fn forward_substitute<T: SimdScalar + Float>(
+    matrix: &CsrMatrix<T>,
+    x: &mut [T],
+    diagonal: impl Fn(usize) -> T,
+) {
+    for i in 0..x.len() {
+        let (indices, values) = matrix.row_entries(i);
+        let end = indices.partition_point(|&j| j < i);
+        let sum = indices[..end]
+            .iter()
+            .zip(&values[..end])
+            .fold(x[i], |acc, (&j, &value)| acc - value * x[j]);
+        x[i] = sum / diagonal(i);
+    }
+}
+
+/// Solves `(D + U)y = x` in place, with `U` the strictly upper triangle of
+/// `matrix` and `D` given by `diagonal`.
+fn back_substitute<T: SimdScalar + Float>(
+    matrix: &CsrMatrix<T>,
+    x: &mut [T],
+    diagonal: impl Fn(usize) -> T,
+) {
+    for i in (0..x.len()).rev() {
+        let (indices, values) = matrix.row_entries(i);
+        let start = indices.partition_point(|&j| j <= i);
+        let sum = indices[start..]
+            .iter()
+            .zip(&values[start..])
+            .fold(x[i], |acc, (&j, &value)| acc - value * x[j]);
+        x[i] = sum / diagonal(i);
+    }
+}
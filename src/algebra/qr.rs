@@ -0,0 +1,239 @@
+use num::Float;
+use rayon::prelude::*;
+
+use crate::{
+    backend,
+    structures::{
+        matrix_simd::{MatrixOpError, SimdMatrix},
+        scalar::SimdScalar,
+        vector_simd::SimdVector,
+    },
+};
+
+/// `AP = QR` factorization from `SimdMatrix::qr` or `SimdMatrix::qr_pivoted`,
+/// where `P` is the identity for the unpivoted form.
+///
+/// `Q` is kept as the Householder reflectors `I - tau * v * vᵀ` that built
+/// it. Each `v` is as long as a column of `A` and is zero above its pivot
+/// row, so applying a reflector is a SIMD dot product and an `axpy`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QR<T: SimdScalar + Float = f32> {
+    r: SimdMatrix<T>,
+    reflectors: Vec<SimdVector<T>>,
+    taus: Vec<T>,
+    permutation: Vec<usize>,
+}
+
+/// Solution of `min ||Ax - b||` from `SimdMatrix::least_squares`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LeastSquares<T: SimdScalar + Float = f32> {
+    pub solution: SimdVector<T>,
+    pub residual_norm: T,
+    /// Number of diagonal entries of `R` above the rank tolerance.
+    pub rank: usize,
+}
+
+impl<T: SimdScalar + Float> SimdMatrix<T> {
+    /// Householder QR. Each reflector is applied to the rows of `R` as
+    /// `vᵀR` followed by one `axpy` per row, both in parallel.
+    pub fn qr(&self) -> QR<T> {
+        self.householder(false)
+    }
+
+    /// Householder QR with column pivoting: every step brings forward the
+    /// remaining column with the largest norm, so the diagonal of `R` is non
+    /// increasing in magnitude and a rank deficiency shows up as its tail.
+    pub fn qr_pivoted(&self) -> QR<T> {
+        self.householder(true)
+    }
+
+    /// Minimises `||self * x - rhs||` with a column pivoted QR. Any columns
+    /// past the numerical rank get a zero coefficient, so rank deficient
+    /// input gives a basic solution rather than the minimum norm one.
+    pub fn least_squares(&self, rhs: &SimdVector<T>) -> Result<LeastSquares<T>, MatrixOpError> {
+        if rhs.len() != self.height() {
+            return Err(MatrixOpError::DimensionMismatch {
+                lhs: self.dimensions(),
+                rhs: (rhs.len(), 1),
+            });
+        }
+        let qr = self.qr_pivoted();
+        let rank = qr.rank();
+        let projected = qr.apply_qt(rhs)?;
+
+        let mut coefficients = SimdVector::zeros(self.row_size);
+        for i in (0..rank).rev() {
+            let row = &qr.r.matrix[i];
+            coefficients[i] = (projected[i] - row.checked_dot(&coefficients).unwrap()) / row[i];
+        }
+        let mut solution = SimdVector::zeros(self.row_size);
+        for (i, &column) in qr.permutation.iter().enumerate() {
+            solution[column] = coefficients[i];
+        }
+        let residual_norm = projected.as_slice()[rank..]
+            .iter()
+            .fold(T::ZERO, |acc, &i| acc + i * i)
+            .sqrt();
+
+        Ok(LeastSquares {
+            solution,
+            residual_norm,
+            rank,
+        })
+    }
+
+    fn householder(&self, pivoting: bool) -> QR<T> {
+        let (height, width) = self.dimensions();
+        let steps = height.min(width);
+        let mut r = self.clone();
+        let mut reflectors = Vec::with_capacity(steps);
+        let mut taus = Vec::with_capacity(steps);
+        let mut permutation: Vec<usize> = (0..width).collect();
+
+        for k in 0..steps {
+            if pivoting {
+                let norms = r.matrix[k..]
+                    .iter()
+                    .fold(SimdVector::zeros(width), |acc, i| {
+                        let mut square = i.clone();
+                        square *= i;
+                        acc + square
+                    });
+                let pivot =
+                    (k..width).fold(k, |acc, j| if norms[j] > norms[acc] { j } else { acc });
+                if pivot != k {
+                    r.matrix
+                        .par_iter_mut()
+                        .for_each(|row| row.as_mut_slice().swap(pivot, k));
+                    permutation.swap(pivot, k);
+                }
+            }
+
+            let mut reflector = SimdVector::zeros(height);
+            for i in k..height {
+                reflector[i] = r.matrix[i][k];
+            }
+            let norm = reflector.checked_dot(&reflector).unwrap().sqrt();
+            if norm == T::ZERO {
+                reflectors.push(reflector);
+                taus.push(T::ZERO);
+                continue;
+            }
+            let alpha = if reflector[k] > T::ZERO { -norm } else { norm };
+            reflector[k] = reflector[k] - alpha;
+            let tau = (T::ONE + T::ONE) / reflector.checked_dot(&reflector).unwrap();
+
+            reflect_rows(&mut r, &reflector, tau, k);
+            r.matrix[k][k] = alpha;
+            for row in &mut r.matrix[k + 1..] {
+                row[k] = T::ZERO;
+            }
+            reflectors.push(reflector);
+            taus.push(tau);
+        }
+
+        QR {
+            r,
+            reflectors,
+            taus,
+            permutation,
+        }
+    }
+}
+
+/// `matrix = (I - tau * v * vᵀ) * matrix`, touching only rows from `start`
+/// on since `v` is zero above it.
+pub(crate) fn reflect_rows<T: SimdScalar + Float>(
+    matrix: &mut SimdMatrix<T>,
+    reflector: &SimdVector<T>,
+    tau: T,
+    start: usize,
+) {
+    let projection = matrix.try_vec_mul(reflector).unwrap();
+    backend::with_current(|backend| {
+        matrix.matrix[start..]
+            .par_iter_mut()
+            .zip(reflector.as_slice()[start..].par_iter())
+            .for_each(|(row, &v)| backend.axpy(-tau * v, &projection, row))
+    });
+}
+
+impl<T: SimdScalar + Float> QR<T> {
+    /// The thin factor, with as many orthonormal columns as `R` has rows in
+    /// `r_thin`.
+    pub fn q_thin(&self) -> SimdMatrix<T> {
+        self.build_q(self.reflectors.len())
+    }
+
+    /// The full square orthogonal factor.
+    pub fn q(&self) -> SimdMatrix<T> {
+        self.build_q(self.r.height())
+    }
+
+    /// The upper triangular (or trapezoidal) factor with `min(rows, columns)`
+    /// rows.
+    pub fn r_thin(&self) -> SimdMatrix<T> {
+        SimdMatrix {
+            matrix: self.r.matrix[..self.reflectors.len()].to_vec(),
+            row_size: self.r.row_size,
+        }
+    }
+
+    /// The factor padded with zero rows to the shape of `A`.
+    pub fn r(&self) -> &SimdMatrix<T> {
+        &self.r
+    }
+
+    /// Column `i` of `AP` is column `permutation()[i]` of `A`.
+    pub fn permutation(&self) -> &[usize] {
+        &self.permutation
+    }
+
+    /// Numerical rank, counting diagonal entries of `R` larger than
+    /// `max(rows, columns) * epsilon * |r_00|`. Only meaningful for the
+    /// pivoted factorization, whose diagonal is sorted by magnitude.
+    pub fn rank(&self) -> usize {
+        let Some(first) = self.r.matrix.first().and_then(|i| i.get(0)) else {
+            return 0;
+        };
+        let (height, width) = self.r.dimensions();
+        let tolerance = T::epsilon() * T::from(height.max(width)).unwrap() * first.abs();
+        (0..self.reflectors.len())
+            .take_while(|&i| self.r.matrix[i][i].abs() > tolerance)
+            .count()
+    }
+
+    /// Computes `Qᵀ * rhs` without forming `Q`.
+    pub fn apply_qt(&self, rhs: &SimdVector<T>) -> Result<SimdVector<T>, MatrixOpError> {
+        if rhs.len() != self.r.height() {
+            return Err(MatrixOpError::DimensionMismatch {
+                lhs: (self.r.height(), self.r.height()),
+                rhs: (rhs.len(), 1),
+            });
+        }
+        let mut out = rhs.clone();
+        for (reflector, &tau) in self.reflectors.iter().zip(&self.taus) {
+            let projection = reflector.checked_dot(&out).unwrap();
+            out.axpy(-tau * projection, reflector);
+        }
+        Ok(out)
+    }
+
+    /// Applies the reflectors in reverse to the first `columns` columns of
+    /// the identity.
+    fn build_q(&self, columns: usize) -> SimdMatrix<T> {
+        let mut q = SimdMatrix::from_fn(self.r.height(), columns, |i, j| {
+            if i == j {
+                T::ONE
+            } else {
+                T::ZERO
+            }
+        });
+        for (k, (reflector, &tau)) in self.reflectors.iter().zip(&self.taus).enumerate().rev() {
+            if tau != T::ZERO {
+                reflect_rows(&mut q, reflector, tau, k);
+            }
+        }
+        q
+    }
+}
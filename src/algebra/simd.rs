@@ -1,17 +1,26 @@
+use std::fmt::Display;
+
+use num::{Complex, Float};
+
 use crate::structures::{
-    matrix_simd::{SimdLineIter, SimdMatrix},
-    vector_simd::SimdVector,
+    complex_simd::{ComplexLineIter, ComplexSimdMatrix, ComplexSimdVector},
+    matrix_simd::{MatrixOpError, SimdLineIter, SimdMatrix},
+    scalar::SimdScalar,
+    vector_simd::{SimdVector, VectorOpError},
 };
 
-use super::vector::{Matrix, Vector};
+use super::{
+    complex::{ComplexMatrix, ComplexVector},
+    vector::{Matrix, Vector},
+};
 
-impl Vector<f32> for SimdVector {
+impl<T: SimdScalar + Float + Display> Vector<T> for SimdVector<T> {
     #[inline]
     fn add(self, rhs: Self) -> Self {
         self + rhs
     }
     #[inline]
-    fn scale(self, scalar: f32) -> Self {
+    fn scale(self, scalar: T) -> Self {
         self.scale(scalar)
     }
     #[inline]
@@ -19,24 +28,69 @@ impl Vector<f32> for SimdVector {
         self * rhs
     }
     #[inline]
-    fn partial_sum(&self) -> f32 {
+    fn sub(self, rhs: Self) -> Self {
+        self - rhs
+    }
+    #[inline]
+    fn elem_div(self, rhs: Self) -> Self {
+        self / rhs
+    }
+    #[inline]
+    fn map(mut self, f: impl Fn(T) -> T + Sync) -> Self {
+        self.as_mut_slice().iter_mut().for_each(|i| *i = f(*i));
+        self
+    }
+    #[inline]
+    fn zip_map(mut self, rhs: Self, f: impl Fn(T, T) -> T + Sync) -> Self {
+        if self.len() != rhs.len() {
+            panic!(
+                "{}",
+                VectorOpError::LengthMismatch {
+                    lhs: self.len(),
+                    rhs: rhs.len()
+                }
+            );
+        }
+        for (a, &b) in self.as_mut_slice().iter_mut().zip(rhs.as_slice()) {
+            *a = f(*a, b);
+        }
+        self
+    }
+    #[inline]
+    fn scale_assign(&mut self, scalar: T) {
+        *self *= scalar;
+    }
+    #[inline]
+    fn add_assign(&mut self, rhs: &Self) {
+        *self += rhs;
+    }
+    #[inline]
+    fn elem_mul_assign(&mut self, rhs: &Self) {
+        *self *= rhs;
+    }
+    #[inline]
+    fn dot(self, rhs: Self) -> T {
+        self.checked_dot(&rhs).unwrap_or_else(|e| panic!("{e}"))
+    }
+    #[inline]
+    fn partial_sum(&self) -> T {
         self.sum()
     }
     #[inline]
-    fn from_vec(input: Vec<f32>) -> Self {
+    fn from_vec(input: Vec<T>) -> Self {
         Self::from_vector(input)
     }
     #[inline]
-    fn to_vec(&self) -> Vec<f32> {
+    fn to_vec(&self) -> Vec<T> {
         self.to_vector()
     }
 }
 
-impl Matrix for SimdMatrix {
-    type Line = SimdVector;
-    type LineIter<'a> = SimdLineIter<'a>;
+impl<T: SimdScalar + Float + Display> Matrix<T> for SimdMatrix<T> {
+    type Line = SimdVector<T>;
+    type LineIter<'a> = SimdLineIter<'a, T>;
     fn dimensions(&self) -> (usize, usize) {
-        (self.height(), self.row_size)
+        self.dimensions()
     }
 
     fn from_vectors(input: Vec<Self::Line>) -> Option<Self> {
@@ -58,4 +112,135 @@ impl Matrix for SimdMatrix {
     fn col_iter<'a>(&'a self) -> Self::LineIter<'a> {
         self.iter_column()
     }
+
+    fn transpose(&self) -> Self {
+        self.transpose()
+    }
+
+    fn mul(self, rhs: Self) -> Option<Self> {
+        self.try_mul(&rhs).ok()
+    }
+
+    fn mul_vec(&self, v: &Self::Line) -> Result<Self::Line, MatrixOpError> {
+        self.try_mul_vec(v)
+    }
+
+    fn vec_mul(&self, v: &Self::Line) -> Result<Self::Line, MatrixOpError> {
+        self.try_vec_mul(v)
+    }
+    fn add(self, rhs: Self) -> Option<Self> {
+        self.try_add(&rhs).ok()
+    }
+
+    fn sub(self, rhs: Self) -> Option<Self> {
+        self.try_sub(&rhs).ok()
+    }
+
+    fn hadamard(self, rhs: Self) -> Option<Self> {
+        self.try_hadamard(&rhs).ok()
+    }
+
+    fn elem_div(self, rhs: Self) -> Option<Self> {
+        self.try_elem_div(&rhs).ok()
+    }
+
+    fn map(&self, f: impl Fn(T) -> T + Sync) -> Self {
+        self.map(f)
+    }
+
+    fn zip_map(&self, rhs: &Self, f: impl Fn(T, T) -> T + Sync) -> Option<Self> {
+        self.try_zip_map(rhs, f).ok()
+    }
+}
+
+impl<T: SimdScalar + Float + Display> ComplexVector<T> for ComplexSimdVector<T> {
+    #[inline]
+    fn scale(self, scalar: Complex<T>) -> Self {
+        ComplexSimdVector::scale(&self, scalar)
+    }
+    #[inline]
+    fn add(self, rhs: Self) -> Self {
+        self + rhs
+    }
+    #[inline]
+    fn elem_mul(self, rhs: Self) -> Self {
+        self * rhs
+    }
+    #[inline]
+    fn conjugate(self) -> Self {
+        self.conjugate()
+    }
+    #[inline]
+    fn from_vec(input: Vec<Complex<T>>) -> Self {
+        Self::from_vector(input)
+    }
+    #[inline]
+    fn to_vec(&self) -> Vec<Complex<T>> {
+        self.to_vector()
+    }
+    #[inline]
+    fn partial_sum(&self) -> Complex<T> {
+        self.sum()
+    }
+    #[inline]
+    fn magnitude(&self) -> T {
+        self.magnitude()
+    }
+    #[inline]
+    fn dot(self, rhs: Self) -> Complex<T> {
+        self.checked_hermitian_dot(&rhs)
+            .unwrap_or_else(|e| panic!("{e}"))
+    }
+}
+
+impl<T: SimdScalar + Float + Display> ComplexMatrix<T> for ComplexSimdMatrix<T> {
+    type Line = ComplexSimdVector<T>;
+    type LineIter<'a> = ComplexLineIter<'a, T>;
+    fn dimensions(&self) -> (usize, usize) {
+        self.dimensions()
+    }
+
+    fn from_vectors(input: Vec<Self::Line>) -> Option<Self> {
+        ComplexSimdMatrix::from_rows(input).ok()
+    }
+
+    fn row(&self, index: usize) -> Option<Self::Line> {
+        self.row(index)
+    }
+
+    fn column(&self, index: usize) -> Option<Self::Line> {
+        self.column(index)
+    }
+
+    fn row_iter<'a>(&'a self) -> Self::LineIter<'a> {
+        self.iter_row()
+    }
+
+    fn col_iter<'a>(&'a self) -> Self::LineIter<'a> {
+        self.iter_column()
+    }
+
+    fn transpose(&self) -> Self {
+        self.transpose()
+    }
+
+    fn conjugate(&self) -> Self {
+        self.clone().conjugate()
+    }
+
+    fn conjugate_transpose(&self) -> Self {
+        self.conjugate_transpose()
+    }
+
+    fn add(self, rhs: Self) -> Option<Self> {
+        self.try_add(&rhs).ok()
+    }
+
+    fn mul(self, rhs: Self) -> Option<Self> {
+        self.try_mul(&rhs).ok()
+    }
+
+    fn mul_vec(&self, v: &Self::Line) -> Result<Self::Line, MatrixOpError> {
+        self.try_mul_vec(v)
+    }
 }
@@ -0,0 +1,391 @@
+use num::Float;
+use thiserror::Error;
+
+use crate::structures::{
+    matrix_simd::{MatrixOpError, SimdMatrix},
+    scalar::SimdScalar,
+    sparse::{CscMatrix, CsrMatrix},
+    vector_simd::SimdVector,
+};
+
+use super::preconditioners::{Identity, Preconditioner};
+
+/// Anything that can multiply a vector, which is all the iterative solvers
+/// need to know about the system matrix.
+///
+/// Implemented for the dense and sparse matrix types and for closures
+/// `Fn(&SimdVector<T>) -> SimdVector<T>`, so matrix-free operators such as a
+/// stencil can be solved without ever being stored.
+pub trait LinearOperator<T: SimdScalar + Float = f32> {
+    fn apply(&self, x: &SimdVector<T>) -> Result<SimdVector<T>, MatrixOpError>;
+}
+
+impl<T: SimdScalar + Float> LinearOperator<T> for SimdMatrix<T> {
+    fn apply(&self, x: &SimdVector<T>) -> Result<SimdVector<T>, MatrixOpError> {
+        self.try_mul_vec(x)
+    }
+}
+
+impl<T: SimdScalar + Float> LinearOperator<T> for CsrMatrix<T> {
+    fn apply(&self, x: &SimdVector<T>) -> Result<SimdVector<T>, MatrixOpError> {
+        self.try_mul_vec(x)
+    }
+}
+
+impl<T: SimdScalar + Float> LinearOperator<T> for CscMatrix<T> {
+    fn apply(&self, x: &SimdVector<T>) -> Result<SimdVector<T>, MatrixOpError> {
+        self.try_mul_vec(x)
+    }
+}
+
+impl<T: SimdScalar + Float, F: Fn(&SimdVector<T>) -> SimdVector<T>> LinearOperator<T> for F {
+    fn apply(&self, x: &SimdVector<T>) -> Result<SimdVector<T>, MatrixOpError> {
+        Ok(self(x))
+    }
+}
+
+/// Stopping criteria shared by the solvers. A solve converges once
+/// `‖b - Ax‖ <= tolerance * ‖b‖`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SolverOptions<T: Float = f32> {
+    pub tolerance: T,
+    /// Upper bound on the number of iterations, each of which costs one
+    /// operator application (two for BiCGSTAB).
+    pub max_iterations: usize,
+    /// Krylov subspace dimension after which GMRES restarts; unused by the
+    /// other solvers.
+    pub restart: usize,
+}
+
+impl<T: Float> Default for SolverOptions<T> {
+    /// A tolerance of `sqrt(epsilon)`, 1000 iterations and restarts every 30.
+    fn default() -> Self {
+        SolverOptions {
+            tolerance: T::epsilon().sqrt(),
+            max_iterations: 1000,
+            restart: 30,
+        }
+    }
+}
+
+/// The outcome of an iterative solve. Running out of iterations is not an
+/// error: `solution` then holds the last iterate and `converged` is false.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SolverReport<T: SimdScalar + Float = f32> {
+    pub solution: SimdVector<T>,
+    pub converged: bool,
+    pub iterations: usize,
+    /// `‖b - Ax‖` for the initial guess and after every iteration. GMRES
+    /// records the residual norm its least-squares problem predicts, which
+    /// matches the true one up to rounding.
+    pub residual_history: Vec<T>,
+}
+
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+pub enum SolverError {
+    #[error("Iterative solver broke down at iteration {iteration}: a step divided by zero or, for conjugate gradient, the operator is not positive definite")]
+    Breakdown { iteration: usize },
+    #[error(transparent)]
+    DimensionMismatch(#[from] MatrixOpError),
+}
+
+/// Conjugate gradient for symmetric positive definite operators, starting
+/// from `guess` or from zero.
+pub fn conjugate_gradient<T: SimdScalar + Float>(
+    operator: &impl LinearOperator<T>,
+    rhs: &SimdVector<T>,
+    guess: Option<&SimdVector<T>>,
+    options: &SolverOptions<T>,
+) -> Result<SolverReport<T>, SolverError> {
+    preconditioned_conjugate_gradient(operator, &Identity, rhs, guess, options)
+}
+
+/// Conjugate gradient with `preconditioner` applying `M⁻¹`, for a symmetric
+/// positive definite `M` approximating the operator. The residual history
+/// still measures the unpreconditioned residual.
+pub fn preconditioned_conjugate_gradient<T: SimdScalar + Float>(
+    operator: &impl LinearOperator<T>,
+    preconditioner: &impl Preconditioner<T>,
+    rhs: &SimdVector<T>,
+    guess: Option<&SimdVector<T>>,
+    options: &SolverOptions<T>,
+) -> Result<SolverReport<T>, SolverError> {
+    let mut state = SolverState::new(operator, rhs, guess, options)?;
+    let mut residual = state.residual.clone();
+    let mut z = preconditioner.apply(&residual);
+    let mut direction = z.clone();
+    let mut rz = residual.checked_dot(&z).unwrap();
+
+    while !state.done() {
+        let image = apply(operator, &direction)?;
+        let curvature = direction.checked_dot(&image).unwrap();
+        if curvature.is_nan() || curvature <= T::ZERO {
+            return Err(state.breakdown());
+        }
+        let alpha = rz / curvature;
+        state.solution.axpy(alpha, &direction);
+        residual.axpy(-alpha, &image);
+        state.record(norm(&residual));
+
+        z = preconditioner.apply(&residual);
+        let rz_next = residual.checked_dot(&z).unwrap();
+        direction *= rz_next / rz;
+        direction += &z;
+        rz = rz_next;
+    }
+    Ok(state.report())
+}
+
+/// Stabilized biconjugate gradient for general nonsymmetric operators,
+/// applying the operator twice per iteration.
+pub fn bicgstab<T: SimdScalar + Float>(
+    operator: &impl LinearOperator<T>,
+    rhs: &SimdVector<T>,
+    guess: Option<&SimdVector<T>>,
+    options: &SolverOptions<T>,
+) -> Result<SolverReport<T>, SolverError> {
+    preconditioned_bicgstab(operator, &Identity, rhs, guess, options)
+}
+
+/// BiCGSTAB preconditioned on the right, i.e. run on `AM⁻¹u = b` with
+/// `x = M⁻¹u`, so the residual history is that of the original system.
+pub fn preconditioned_bicgstab<T: SimdScalar + Float>(
+    operator: &impl LinearOperator<T>,
+    preconditioner: &impl Preconditioner<T>,
+    rhs: &SimdVector<T>,
+    guess: Option<&SimdVector<T>>,
+    options: &SolverOptions<T>,
+) -> Result<SolverReport<T>, SolverError> {
+    let mut state = SolverState::new(operator, rhs, guess, options)?;
+    let mut residual = state.residual.clone();
+    let shadow = residual.clone();
+    let (mut rho, mut alpha, mut omega) = (T::ONE, T::ONE, T::ONE);
+    let mut direction = SimdVector::zeros(rhs.len());
+    let mut image = SimdVector::zeros(rhs.len());
+
+    while !state.done() {
+        let rho_next = shadow.checked_dot(&residual).unwrap();
+        if rho_next == T::ZERO || omega == T::ZERO {
+            return Err(state.breakdown());
+        }
+        // p = r + beta * (p - omega * v)
+        direction.axpy(-omega, &image);
+        direction *= (rho_next / rho) * (alpha / omega);
+        direction += &residual;
+        rho = rho_next;
+
+        let preconditioned = preconditioner.apply(&direction);
+        image = apply(operator, &preconditioned)?;
+        let projection = shadow.checked_dot(&image).unwrap();
+        if projection == T::ZERO {
+            return Err(state.breakdown());
+        }
+        alpha = rho / projection;
+        residual.axpy(-alpha, &image);
+        state.solution.axpy(alpha, &preconditioned);
+        if norm(&residual) <= state.threshold {
+            state.record(norm(&residual));
+            break;
+        }
+
+        let preconditioned = preconditioner.apply(&residual);
+        let stabilizer = apply(operator, &preconditioned)?;
+        let stabilizer_squared = stabilizer.checked_dot(&stabilizer).unwrap();
+        omega = if stabilizer_squared == T::ZERO {
+            T::ZERO
+        } else {
+            stabilizer.checked_dot(&residual).unwrap() / stabilizer_squared
+        };
+        state.solution.axpy(omega, &preconditioned);
+        residual.axpy(-omega, &stabilizer);
+        state.record(norm(&residual));
+    }
+    Ok(state.report())
+}
+
+/// Restarted GMRES for general operators. Each cycle builds an orthonormal
+/// Krylov basis of up to `options.restart` vectors by modified Gram-Schmidt
+/// and reduces the Hessenberg least-squares problem with Givens rotations as
+/// it goes, so the residual norm is known at every step without forming `x`.
+pub fn gmres<T: SimdScalar + Float>(
+    operator: &impl LinearOperator<T>,
+    rhs: &SimdVector<T>,
+    guess: Option<&SimdVector<T>>,
+    options: &SolverOptions<T>,
+) -> Result<SolverReport<T>, SolverError> {
+    preconditioned_gmres(operator, &Identity, rhs, guess, options)
+}
+
+/// GMRES preconditioned on the right: the Krylov basis is built for `AM⁻¹`
+/// and the update is mapped back through `M⁻¹` at the end of every cycle, so
+/// the minimized residual is that of the original system.
+pub fn preconditioned_gmres<T: SimdScalar + Float>(
+    operator: &impl LinearOperator<T>,
+    preconditioner: &impl Preconditioner<T>,
+    rhs: &SimdVector<T>,
+    guess: Option<&SimdVector<T>>,
+    options: &SolverOptions<T>,
+) -> Result<SolverReport<T>, SolverError> {
+    let mut state = SolverState::new(operator, rhs, guess, options)?;
+    let restart = options.restart.max(1);
+
+    while !state.done() {
+        let beta = norm(&state.residual);
+        let mut basis = vec![&state.residual / beta];
+        // Column `j` of the Hessenberg matrix, already rotated to upper
+        // triangular form.
+        let mut columns: Vec<Vec<T>> = vec![];
+        let mut rotations: Vec<(T, T)> = vec![];
+        let mut projected = vec![beta];
+
+        for j in 0..restart {
+            let mut w = apply(operator, &preconditioner.apply(&basis[j]))?;
+            let mut column: Vec<T> = basis
+                .iter()
+                .map(|v| {
+                    let h = w.checked_dot(v).unwrap();
+                    w.axpy(-h, v);
+                    h
+                })
+                .collect();
+            let next = norm(&w);
+            column.push(next);
+            for (i, &(c, s)) in rotations.iter().enumerate() {
+                let (a, b) = (column[i], column[i + 1]);
+                column[i] = c * a + s * b;
+                column[i + 1] = c * b - s * a;
+            }
+            let diagonal = column[j].hypot(column[j + 1]);
+            if diagonal == T::ZERO {
+                return Err(state.breakdown());
+            }
+            let (c, s) = (column[j] / diagonal, column[j + 1] / diagonal);
+            column[j] = diagonal;
+            column.truncate(j + 1);
+            rotations.push((c, s));
+            columns.push(column);
+            projected.push(-s * projected[j]);
+            projected[j] = c * projected[j];
+            state.record(projected[j + 1].abs());
+
+            if state.done() || next == T::ZERO {
+                break;
+            }
+            basis.push(w / next);
+        }
+
+        // Back substitution on the triangular system, then x += M⁻¹Vy.
+        let size = columns.len();
+        let mut y = projected[..size].to_vec();
+        for i in (0..size).rev() {
+            let value = y[i] / columns[i][i];
+            y[i] = value;
+            for (target, &entry) in y[..i].iter_mut().zip(&columns[i][..i]) {
+                *target = *target - entry * value;
+            }
+        }
+        let mut update = SimdVector::zeros(rhs.len());
+        for (v, &coefficient) in basis.iter().zip(&y) {
+            update.axpy(coefficient, v);
+        }
+        state.solution += &preconditioner.apply(&update);
+        state.residual = residual(operator, rhs, &state.solution)?;
+    }
+    Ok(state.report())
+}
+
+/// Bookkeeping shared by the solvers: the iterate, the residual history and
+/// the stopping test.
+struct SolverState<T: SimdScalar + Float> {
+    solution: SimdVector<T>,
+    residual: SimdVector<T>,
+    threshold: T,
+    max_iterations: usize,
+    iterations: usize,
+    history: Vec<T>,
+}
+
+impl<T: SimdScalar + Float> SolverState<T> {
+    fn new(
+        operator: &impl LinearOperator<T>,
+        rhs: &SimdVector<T>,
+        guess: Option<&SimdVector<T>>,
+        options: &SolverOptions<T>,
+    ) -> Result<SolverState<T>, MatrixOpError> {
+        let solution = match guess {
+            Some(guess) if guess.len() != rhs.len() => {
+                return Err(MatrixOpError::DimensionMismatch {
+                    lhs: (rhs.len(), rhs.len()),
+                    rhs: (guess.len(), 1),
+                })
+            }
+            Some(guess) => guess.clone(),
+            None => SimdVector::zeros(rhs.len()),
+        };
+        let residual = residual(operator, rhs, &solution)?;
+        Ok(SolverState {
+            threshold: options.tolerance * norm(rhs),
+            max_iterations: options.max_iterations,
+            iterations: 0,
+            history: vec![norm(&residual)],
+            solution,
+            residual,
+        })
+    }
+
+    fn record(&mut self, residual_norm: T) {
+        self.iterations += 1;
+        self.history.push(residual_norm);
+    }
+
+    fn converged(&self) -> bool {
+        self.history.last().is_some_and(|&i| i <= self.threshold)
+    }
+
+    fn done(&self) -> bool {
+        self.converged() || self.iterations >= self.max_iterations
+    }
+
+    fn breakdown(&self) -> SolverError {
+        SolverError::Breakdown {
+            iteration: self.iterations,
+        }
+    }
+
+    fn report(self) -> SolverReport<T> {
+        SolverReport {
+            converged: self.converged(),
+            solution: self.solution,
+            iterations: self.iterations,
+            residual_history: self.history,
+        }
+    }
+}
+
+/// Applies a square operator, checking that it preserved the length.
+fn apply<T: SimdScalar + Float>(
+    operator: &impl LinearOperator<T>,
+    x: &SimdVector<T>,
+) -> Result<SimdVector<T>, MatrixOpError> {
+    let image = operator.apply(x)?;
+    if image.len() == x.len() {
+        Ok(image)
+    } else {
+        Err(MatrixOpError::DimensionMismatch {
+            lhs: (image.len(), x.len()),
+            rhs: (x.len(), 1),
+        })
+    }
+}
+
+fn residual<T: SimdScalar + Float>(
+    operator: &impl LinearOperator<T>,
+    rhs: &SimdVector<T>,
+    x: &SimdVector<T>,
+) -> Result<SimdVector<T>, MatrixOpError> {
+    Ok(rhs - &apply(operator, x)?)
+}
+
+fn norm<T: SimdScalar + Float>(x: &SimdVector<T>) -> T {
+    x.checked_dot(x).unwrap().sqrt()
+}
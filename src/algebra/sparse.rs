@@ -0,0 +1,115 @@
+use std::fmt::Display;
+
+use num::Float;
+
+use crate::structures::{
+    matrix_simd::{MatrixOpError, SimdMatrix},
+    scalar::SimdScalar,
+    sparse::{CscMatrix, CsrMatrix, SparseLineIter},
+    vector_simd::SimdVector,
+};
+
+use super::vector::Matrix;
+
+// Lines are handed out as dense `SimdVector`s, so generic code sees the same
+// line type as for `SimdMatrix`. Everything that does not need a line at a
+// time is overridden to stay sparse.
+impl<T: SimdScalar + Float + Display> Matrix<T> for CsrMatrix<T> {
+    type Line = SimdVector<T>;
+    type LineIter<'a> = SparseLineIter<'a, T>;
+    fn dimensions(&self) -> (usize, usize) {
+        self.dimensions()
+    }
+
+    fn from_vectors(input: Vec<Self::Line>) -> Option<Self> {
+        SimdMatrix::from_simd(input)
+            .ok()
+            .map(|i| CsrMatrix::from_dense(&i))
+    }
+
+    fn row(&self, index: usize) -> Option<Self::Line> {
+        (index < self.dimensions().0).then(|| self.dense_row(index))
+    }
+
+    fn column(&self, index: usize) -> Option<Self::Line> {
+        (index < self.dimensions().1).then(|| self.dense_column(index))
+    }
+
+    fn row_iter<'a>(&'a self) -> Self::LineIter<'a> {
+        self.iter_row()
+    }
+
+    fn col_iter<'a>(&'a self) -> Self::LineIter<'a> {
+        self.iter_column()
+    }
+
+    fn transpose(&self) -> Self {
+        self.transpose()
+    }
+
+    fn scale(&self, rhs: T) -> Self {
+        self.clone().scale(rhs)
+    }
+
+    fn mul(self, rhs: Self) -> Option<Self> {
+        self.try_mul_sparse(&rhs).ok()
+    }
+
+    fn mul_vec(&self, v: &Self::Line) -> Result<Self::Line, MatrixOpError> {
+        self.try_mul_vec(v)
+    }
+
+    fn vec_mul(&self, v: &Self::Line) -> Result<Self::Line, MatrixOpError> {
+        self.try_vec_mul(v)
+    }
+}
+
+impl<T: SimdScalar + Float + Display> Matrix<T> for CscMatrix<T> {
+    type Line = SimdVector<T>;
+    type LineIter<'a> = SparseLineIter<'a, T>;
+    fn dimensions(&self) -> (usize, usize) {
+        self.dimensions()
+    }
+
+    fn from_vectors(input: Vec<Self::Line>) -> Option<Self> {
+        SimdMatrix::from_simd(input)
+            .ok()
+            .map(|i| CscMatrix::from_dense(&i))
+    }
+
+    fn row(&self, index: usize) -> Option<Self::Line> {
+        (index < self.dimensions().0).then(|| self.dense_row(index))
+    }
+
+    fn column(&self, index: usize) -> Option<Self::Line> {
+        (index < self.dimensions().1).then(|| self.dense_column(index))
+    }
+
+    fn row_iter<'a>(&'a self) -> Self::LineIter<'a> {
+        self.iter_row()
+    }
+
+    fn col_iter<'a>(&'a self) -> Self::LineIter<'a> {
+        self.iter_column()
+    }
+
+    fn transpose(&self) -> Self {
+        self.transpose()
+    }
+
+    fn scale(&self, rhs: T) -> Self {
+        self.clone().scale(rhs)
+    }
+
+    fn mul(self, rhs: Self) -> Option<Self> {
+        self.try_mul_sparse(&rhs).ok()
+    }
+
+    fn mul_vec(&self, v: &Self::Line) -> Result<Self::Line, MatrixOpError> {
+        self.try_mul_vec(v)
+    }
+
+    fn vec_mul(&self, v: &Self::Line) -> Result<Self::Line, MatrixOpError> {
+        self.try_vec_mul(v)
+    }
+}
@@ -0,0 +1,227 @@
+use num::Float;
+use thiserror::Error;
+
+use crate::structures::{matrix_simd::SimdMatrix, scalar::SimdScalar, vector_simd::SimdVector};
+
+use super::eigen::{rotate_rows, MAX_SWEEPS};
+
+/// `A = UΣVᵀ` from `SimdMatrix::svd` or `SimdMatrix::svd_full`.
+///
+/// For an `m x n` matrix with `k = min(m, n)`, the thin form has a `m x k`
+/// `u` and a `k x n` `v_t`; the full form has square `u` and `v_t`. There
+/// are always `k` singular values, in decreasing order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SVD<T: SimdScalar + Float = f32> {
+    pub u: SimdMatrix<T>,
+    pub singular_values: SimdVector<T>,
+    pub v_t: SimdMatrix<T>,
+}
+
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+pub enum SvdError {
+    #[error("Singular value decomposition did not converge within {iterations} sweeps")]
+    NoConvergence { iterations: usize },
+}
+
+impl<T: SimdScalar + Float> SimdMatrix<T> {
+    /// Thin singular value decomposition by one-sided Jacobi rotations.
+    ///
+    /// The columns of the taller orientation are kept as SIMD rows and
+    /// rotated in pairs until they are mutually orthogonal; their norms are
+    /// then the singular values. This gets small singular values to high
+    /// relative accuracy.
+    pub fn svd(&self) -> Result<SVD<T>, SvdError> {
+        self.jacobi_svd(false)
+    }
+
+    /// Like `svd`, but with `u` and `v_t` completed to square orthogonal
+    /// matrices.
+    pub fn svd_full(&self) -> Result<SVD<T>, SvdError> {
+        self.jacobi_svd(true)
+    }
+
+    fn jacobi_svd(&self, full: bool) -> Result<SVD<T>, SvdError> {
+        let (height, width) = self.dimensions();
+        if height >= width {
+            orthogonalize_columns(self.transpose().matrix, height, full)
+        } else {
+            // Decompose `Aᵀ = VΣUᵀ`, whose columns are the rows of `A`.
+            let transposed = orthogonalize_columns(self.matrix.clone(), width, full)?;
+            Ok(SVD {
+                u: transposed.v_t.transpose(),
+                singular_values: transposed.singular_values,
+                v_t: transposed.u.transpose(),
+            })
+        }
+    }
+}
+
+/// One-sided Jacobi on the columns of a tall matrix, given as rows of length
+/// `height`, with `u` completed to a square matrix if `full` is set.
+fn orthogonalize_columns<T: SimdScalar + Float>(
+    columns: Vec<SimdVector<T>>,
+    height: usize,
+    full: bool,
+) -> Result<SVD<T>, SvdError> {
+    let width = columns.len();
+    let mut work = SimdMatrix {
+        matrix: columns,
+        row_size: height,
+    };
+    let mut v_t = SimdMatrix::identity(width);
+
+    let mut converged = false;
+    for _ in 0..MAX_SWEEPS {
+        let mut rotated = false;
+        for p in 0..width {
+            for q in p + 1..width {
+                let (row_p, row_q) = (&work.matrix[p], &work.matrix[q]);
+                let alpha = row_p.checked_dot(row_p).unwrap();
+                let beta = row_q.checked_dot(row_q).unwrap();
+                let gamma = row_p.checked_dot(row_q).unwrap();
+                if gamma.abs() <= T::epsilon() * (alpha * beta).sqrt() {
+                    continue;
+                }
+                rotated = true;
+                let zeta = (beta - alpha) / ((T::ONE + T::ONE) * gamma);
+                let tangent = zeta.signum() / (zeta.abs() + zeta.hypot(T::ONE));
+                let cosine = T::ONE / tangent.hypot(T::ONE);
+                let sine = tangent * cosine;
+                rotate_rows(&mut work, p, q, cosine, sine);
+                rotate_rows(&mut v_t, p, q, cosine, sine);
+            }
+        }
+        if !rotated {
+            converged = true;
+            break;
+        }
+    }
+    if !converged {
+        return Err(SvdError::NoConvergence {
+            iterations: MAX_SWEEPS,
+        });
+    }
+
+    let norms: Vec<T> = work
+        .matrix
+        .iter()
+        .map(|i| i.checked_dot(i).unwrap().sqrt())
+        .collect();
+    let mut order: Vec<usize> = (0..width).collect();
+    order.sort_by(|&i, &j| norms[j].partial_cmp(&norms[i]).unwrap());
+    let largest = order.first().map_or(T::ZERO, |&i| norms[i]);
+    let negligible = T::epsilon() * T::from(height).unwrap() * largest;
+
+    // Columns with a negligible norm carry no direction, so their left
+    // singular vectors are filled in afterwards from an orthonormal basis.
+    let mut u_t: Vec<SimdVector<T>> = order
+        .iter()
+        .filter(|&&i| norms[i] > negligible)
+        .map(|&i| &work.matrix[i] / norms[i])
+        .collect();
+    complete_basis(&mut u_t, if full { height } else { width }, height);
+
+    Ok(SVD {
+        u: SimdMatrix {
+            matrix: u_t,
+            row_size: height,
+        }
+        .transpose(),
+        singular_values: SimdVector::from_vector(order.iter().map(|&i| norms[i]).collect()),
+        v_t: SimdMatrix {
+            matrix: order.iter().map(|&i| v_t.matrix[i].clone()).collect(),
+            row_size: width,
+        },
+    })
+}
+
+/// Extends the orthonormal rows of `basis` to `target` rows by orthogonalizing
+/// unit vectors against it. Some unit vector always keeps a squared norm of at
+/// least `1 / size` after projection, so anything under a quarter of that is
+/// skipped as too close to the existing span.
+fn complete_basis<T: SimdScalar + Float>(
+    basis: &mut Vec<SimdVector<T>>,
+    target: usize,
+    size: usize,
+) {
+    let threshold = T::ONE / T::from(4 * size).unwrap();
+    for i in 0..size {
+        if basis.len() >= target {
+            return;
+        }
+        let mut candidate = SimdVector::zeros(size);
+        candidate[i] = T::ONE;
+        // Projecting twice keeps the result orthogonal to working precision.
+        for _ in 0..2 {
+            for vector in basis.iter() {
+                candidate.axpy(-vector.checked_dot(&candidate).unwrap(), vector);
+            }
+        }
+        let norm = candidate.checked_dot(&candidate).unwrap();
+        if norm >= threshold {
+            basis.push(candidate / norm.sqrt());
+        }
+    }
+}
+
+impl<T: SimdScalar + Float> SVD<T> {
+    /// `max(m, n) * epsilon * σ_max`, the cutoff `rank` and `pseudo_inverse`
+    /// use when not given one.
+    pub fn default_tolerance(&self) -> T {
+        let size = self.u.height().max(self.v_t.row_size);
+        T::epsilon() * T::from(size).unwrap() * self.singular_values.get(0).unwrap_or(T::ZERO)
+    }
+
+    /// Number of singular values above `tolerance`, or above
+    /// `default_tolerance` if it is `None`.
+    pub fn rank(&self, tolerance: Option<T>) -> usize {
+        let tolerance = tolerance.unwrap_or_else(|| self.default_tolerance());
+        self.singular_values
+            .iter()
+            .take_while(|&i| i > tolerance)
+            .count()
+    }
+
+    /// `σ_max / σ_min` in the 2-norm, infinite for rank deficient matrices
+    /// and one for empty ones.
+    pub fn condition_number(&self) -> T {
+        let values = self.singular_values.as_slice();
+        match (values.first(), values.last()) {
+            (Some(&largest), Some(&smallest)) => largest / smallest,
+            _ => T::ONE,
+        }
+    }
+
+    /// Moore-Penrose pseudo-inverse `VΣ⁺Uᵀ`, inverting only the singular values
+    /// above `tolerance` as in `rank`.
+    pub fn pseudo_inverse(&self, tolerance: Option<T>) -> SimdMatrix<T> {
+        let rank = self.rank(tolerance);
+        let u_t = self.u.transpose();
+        let scaled = SimdMatrix {
+            matrix: (0..rank)
+                .map(|i| &u_t.matrix[i] / self.singular_values[i])
+                .collect(),
+            row_size: u_t.row_size,
+        };
+        let v = SimdMatrix {
+            matrix: self.v_t.matrix[..rank].to_vec(),
+            row_size: self.v_t.row_size,
+        }
+        .transpose();
+        &v * &scaled
+    }
+
+    /// Best rank `k` approximation of the original matrix in the 2-norm and
+    /// the Frobenius norm, `U_k Σ_k V_kᵀ`.
+    pub fn truncated(&self, k: usize) -> SimdMatrix<T> {
+        let k = k.min(self.singular_values.len());
+        let scaled = SimdMatrix::from_fn(self.u.height(), k, |i, j| {
+            self.u.matrix[i][j] * self.singular_values[j]
+        });
+        let v_t = SimdMatrix {
+            matrix: self.v_t.matrix[..k].to_vec(),
+            row_size: self.v_t.row_size,
+        };
+        &scaled * &v_t
+    }
+}
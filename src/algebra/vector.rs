@@ -2,6 +2,8 @@ use std::fmt::Debug;
 
 use num::Float;
 
+use crate::structures::{matrix_simd::MatrixOpError, vector_simd::VectorOpError};
+
 pub trait Vector<Scalar: Float = f32>
 where
     Self: Sized + Clone,
@@ -11,17 +13,60 @@ where
     fn add(self, rhs: Self) -> Self;
     fn elem_mul(self, rhs: Self) -> Self;
 
+    fn sub(self, rhs: Self) -> Self {
+        self.add(rhs.scale(-Scalar::one()))
+    }
+    fn elem_div(self, rhs: Self) -> Self {
+        self.zip_map(rhs, |a, b| a / b)
+    }
+
+    /// Applies `f` to every element.
+    fn map(self, f: impl Fn(Scalar) -> Scalar + Sync) -> Self {
+        Self::from_vec(self.to_vec().into_iter().map(f).collect())
+    }
+
+    /// Combines each element with the matching element of `rhs` through `f`.
+    /// Panics if the lengths differ, like the elementwise operators.
+    fn zip_map(self, rhs: Self, f: impl Fn(Scalar, Scalar) -> Scalar + Sync) -> Self {
+        let (lhs, rhs) = (self.to_vec(), rhs.to_vec());
+        if lhs.len() != rhs.len() {
+            panic!(
+                "{}",
+                VectorOpError::LengthMismatch {
+                    lhs: lhs.len(),
+                    rhs: rhs.len()
+                }
+            );
+        }
+        Self::from_vec(lhs.into_iter().zip(rhs).map(|(a, b)| f(a, b)).collect())
+    }
+
+    // In-place forms, so callers holding a borrowed operand do not have to
+    // clone it. Implementors should override these to reuse their buffers.
+    fn scale_assign(&mut self, scalar: Scalar) {
+        *self = self.clone().scale(scalar);
+    }
+    fn add_assign(&mut self, rhs: &Self) {
+        *self = self.clone().add(rhs.clone());
+    }
+    fn elem_mul_assign(&mut self, rhs: &Self) {
+        *self = self.clone().elem_mul(rhs.clone());
+    }
+
     fn from_vec(input: Vec<Scalar>) -> Self;
     fn to_vec(&self) -> Vec<Scalar>;
 
     fn partial_sum(&self) -> Scalar;
 
     fn magnitude(&self) -> Scalar {
-        self.clone().elem_mul(self.clone()).partial_sum().sqrt()
+        let mut squares = self.clone();
+        squares.elem_mul_assign(self);
+        squares.partial_sum().sqrt()
     }
 
-    fn dot(self, rhs: Self) -> Scalar {
-        self.elem_mul(rhs).partial_sum()
+    fn dot(mut self, rhs: Self) -> Scalar {
+        self.elem_mul_assign(&rhs);
+        self.partial_sum()
     }
 
     fn dot_debug(self, rhs: Self) -> Scalar {
@@ -48,18 +93,59 @@ where
     fn row_iter<'a>(&'a self) -> Self::LineIter<'a>;
     fn col_iter<'a>(&'a self) -> Self::LineIter<'a>;
 
+    fn transpose(&self) -> Self {
+        Self::from_vectors(self.col_iter().collect()).unwrap()
+    }
+
     fn scale(&self, rhs: Scalar) -> Self {
-        Self::from_vectors(self.row_iter().map(|i| i.scale(rhs.clone())).collect()).unwrap()
+        Self::from_vectors(self.row_iter().map(|i| i.scale(rhs)).collect()).unwrap()
     }
     fn mul(self, rhs: Self) -> Option<Self> {
         if self.dimensions().1 != rhs.dimensions().0 {
             return None;
         }
-        Some(Self::from_vectors(
+        Self::from_vectors(
             self.row_iter()
-                .map(|i| Self::Line::from_vec(rhs.col_iter().map(|j| j.dot(i.clone())).collect()))
+                .map(|i| {
+                    Self::Line::from_vec(
+                        rhs.col_iter()
+                            .map(|mut j| {
+                                j.elem_mul_assign(&i);
+                                j.partial_sum()
+                            })
+                            .collect(),
+                    )
+                })
                 .collect(),
-        )?)
+        )
+    }
+
+    /// Matrix-vector product `self * v`.
+    fn mul_vec(&self, v: &Self::Line) -> Result<Self::Line, MatrixOpError> {
+        let length = v.to_vec().len();
+        if self.dimensions().1 != length {
+            return Err(MatrixOpError::DimensionMismatch {
+                lhs: self.dimensions(),
+                rhs: (length, 1),
+            });
+        }
+        Ok(Self::Line::from_vec(
+            self.row_iter().map(|i| i.dot(v.clone())).collect(),
+        ))
+    }
+
+    /// Vector-matrix product `vᵀ * self`.
+    fn vec_mul(&self, v: &Self::Line) -> Result<Self::Line, MatrixOpError> {
+        let length = v.to_vec().len();
+        if self.dimensions().0 != length {
+            return Err(MatrixOpError::DimensionMismatch {
+                lhs: (1, length),
+                rhs: self.dimensions(),
+            });
+        }
+        Ok(Self::Line::from_vec(
+            self.col_iter().map(|i| i.dot(v.clone())).collect(),
+        ))
     }
 
     fn mul_debug(self, rhs: Self) -> Option<Self> {
@@ -83,8 +169,7 @@ where
                     })
                     .collect();
                 println!("{:?}", output.clone());
-                let out_simd = Self::Line::from_vec(output);
-                out_simd
+                Self::Line::from_vec(output)
             })
             .collect();
 
@@ -92,15 +177,56 @@ where
         Some(Self::from_vectors(vectors).unwrap())
     }
 
+    // The elementwise operations pair row `i` of `self` with row `i` of
+    // `rhs`, and return `None` when the dimensions differ.
     fn add(self, rhs: Self) -> Option<Self> {
+        zip_rows(self, rhs, Vector::add)
+    }
+
+    fn sub(self, rhs: Self) -> Option<Self> {
+        zip_rows(self, rhs, Vector::sub)
+    }
+
+    /// Elementwise product.
+    fn hadamard(self, rhs: Self) -> Option<Self> {
+        zip_rows(self, rhs, Vector::elem_mul)
+    }
+
+    fn elem_div(self, rhs: Self) -> Option<Self> {
+        zip_rows(self, rhs, Vector::elem_div)
+    }
+
+    /// Applies `f` to every element.
+    fn map(&self, f: impl Fn(Scalar) -> Scalar + Sync) -> Self {
+        Self::from_vectors(self.row_iter().map(|i| i.map(&f)).collect()).unwrap()
+    }
+
+    /// Combines each element with the matching element of `rhs` through `f`.
+    fn zip_map(&self, rhs: &Self, f: impl Fn(Scalar, Scalar) -> Scalar + Sync) -> Option<Self> {
         if self.dimensions() != rhs.dimensions() {
             return None;
         }
         Self::from_vectors(
             self.row_iter()
-                .zip(rhs.col_iter())
-                .map(|(i, j)| i.add(j))
+                .zip(rhs.row_iter())
+                .map(|(i, j)| i.zip_map(j, &f))
                 .collect(),
         )
     }
 }
+
+fn zip_rows<Scalar, M>(lhs: M, rhs: M, f: impl Fn(M::Line, M::Line) -> M::Line) -> Option<M>
+where
+    Scalar: Float + Debug + std::fmt::Display,
+    M: Matrix<Scalar>,
+{
+    if lhs.dimensions() != rhs.dimensions() {
+        return None;
+    }
+    M::from_vectors(
+        lhs.row_iter()
+            .zip(rhs.row_iter())
+            .map(|(i, j)| f(i, j))
+            .collect(),
+    )
+}
@@ -1,9 +1,11 @@
-use std::{async_iter::AsyncIterator, fmt::Debug};
+use std::fmt::Debug;
 
-use async_trait_fn::{async_trait, unboxed};
+use async_trait::async_trait;
 use futures::future::join_all;
 use num::Float;
 
+use crate::structures::vector_simd::VectorOpError;
+
 #[async_trait]
 pub trait AsyncVector<Scalar: Float = f32>
 where
@@ -14,6 +16,40 @@ where
     async fn add(self, rhs: Self) -> Self;
     async fn elem_mul(self, rhs: Self) -> Self;
 
+    async fn sub(self, rhs: Self) -> Self {
+        self.add(rhs.scale(-Scalar::one()).await).await
+    }
+    async fn elem_div(self, rhs: Self) -> Self {
+        self.zip_map(rhs, |a, b| a / b).await
+    }
+
+    /// Applies `f` to every element.
+    async fn map<F>(self, f: F) -> Self
+    where
+        F: Fn(Scalar) -> Scalar + Send + Sync,
+    {
+        Self::from_vec(self.to_vec().await.into_iter().map(f).collect()).await
+    }
+
+    /// Combines each element with the matching element of `rhs` through `f`.
+    /// Panics if the lengths differ, like the elementwise operators.
+    async fn zip_map<F>(self, rhs: Self, f: F) -> Self
+    where
+        F: Fn(Scalar, Scalar) -> Scalar + Send + Sync,
+    {
+        let (lhs, rhs) = (self.to_vec().await, rhs.to_vec().await);
+        if lhs.len() != rhs.len() {
+            panic!(
+                "{}",
+                VectorOpError::LengthMismatch {
+                    lhs: lhs.len(),
+                    rhs: rhs.len()
+                }
+            );
+        }
+        Self::from_vec(lhs.into_iter().zip(rhs).map(|(a, b)| f(a, b)).collect()).await
+    }
+
     async fn from_vec(input: Vec<Scalar>) -> Self;
     async fn to_vec(&self) -> Vec<Scalar>;
 
@@ -56,7 +92,7 @@ where
         Self::from_vectors(
             join_all(
                 self.row_iter()
-                    .map(|i| async { i.scale(rhs.clone()).await })
+                    .map(|i| async { i.scale(*rhs).await })
                     .collect::<Vec<_>>(),
             )
             .await,
@@ -91,6 +127,8 @@ where
         )
     }
 
+    // The elementwise operations pair row `i` of `self` with row `i` of
+    // `rhs`, and return `None` when the dimensions differ.
     async fn add(self, rhs: Self) -> Option<Self> {
         if self.dimensions() != rhs.dimensions() {
             return None;
@@ -98,7 +136,7 @@ where
         Self::from_vectors(
             join_all(
                 self.row_iter()
-                    .zip(rhs.col_iter())
+                    .zip(rhs.row_iter())
                     .map(|(i, j)| async { i.add(j).await })
                     .collect::<Vec<_>>(),
             )
@@ -106,4 +144,90 @@ where
         )
         .await
     }
+
+    async fn sub(self, rhs: Self) -> Option<Self> {
+        if self.dimensions() != rhs.dimensions() {
+            return None;
+        }
+        Self::from_vectors(
+            join_all(
+                self.row_iter()
+                    .zip(rhs.row_iter())
+                    .map(|(i, j)| async { i.sub(j).await })
+                    .collect::<Vec<_>>(),
+            )
+            .await,
+        )
+        .await
+    }
+
+    /// Elementwise product.
+    async fn hadamard(self, rhs: Self) -> Option<Self> {
+        if self.dimensions() != rhs.dimensions() {
+            return None;
+        }
+        Self::from_vectors(
+            join_all(
+                self.row_iter()
+                    .zip(rhs.row_iter())
+                    .map(|(i, j)| async { i.elem_mul(j).await })
+                    .collect::<Vec<_>>(),
+            )
+            .await,
+        )
+        .await
+    }
+
+    async fn elem_div(self, rhs: Self) -> Option<Self> {
+        if self.dimensions() != rhs.dimensions() {
+            return None;
+        }
+        Self::from_vectors(
+            join_all(
+                self.row_iter()
+                    .zip(rhs.row_iter())
+                    .map(|(i, j)| async { i.elem_div(j).await })
+                    .collect::<Vec<_>>(),
+            )
+            .await,
+        )
+        .await
+    }
+
+    /// Applies `f` to every element.
+    async fn map<F>(&self, f: F) -> Self
+    where
+        F: Fn(Scalar) -> Scalar + Send + Sync,
+    {
+        Self::from_vectors(
+            join_all(
+                self.row_iter()
+                    .map(|i| async { i.map(&f).await })
+                    .collect::<Vec<_>>(),
+            )
+            .await,
+        )
+        .await
+        .unwrap()
+    }
+
+    /// Combines each element with the matching element of `rhs` through `f`.
+    async fn zip_map<F>(&self, rhs: &Self, f: F) -> Option<Self>
+    where
+        F: Fn(Scalar, Scalar) -> Scalar + Send + Sync,
+    {
+        if self.dimensions() != rhs.dimensions() {
+            return None;
+        }
+        Self::from_vectors(
+            join_all(
+                self.row_iter()
+                    .zip(rhs.row_iter())
+                    .map(|(i, j)| async { i.zip_map(j, &f).await })
+                    .collect::<Vec<_>>(),
+            )
+            .await,
+        )
+        .await
+    }
 }
@@ -0,0 +1,436 @@
+use std::os::raw::{c_char, c_int};
+
+use num::Float;
+
+use crate::{
+    algebra::{
+        cholesky::{Cholesky, CholeskyError},
+        lu::{LuError, LU},
+    },
+    structures::{matrix_simd::SimdMatrix, scalar::SimdScalar, vector_simd::SimdVector},
+};
+
+use super::{Backend, ElementwiseOp, SimdBackend};
+
+/// `CblasRowMajor`.
+const ROW_MAJOR: c_int = 101;
+/// `CblasNoTrans`.
+const NO_TRANS: c_int = 111;
+
+#[link(name = "blas")]
+extern "C" {
+    fn cblas_sdot(n: c_int, x: *const f32, incx: c_int, y: *const f32, incy: c_int) -> f32;
+    fn cblas_ddot(n: c_int, x: *const f64, incx: c_int, y: *const f64, incy: c_int) -> f64;
+    fn cblas_saxpy(n: c_int, alpha: f32, x: *const f32, incx: c_int, y: *mut f32, incy: c_int);
+    fn cblas_daxpy(n: c_int, alpha: f64, x: *const f64, incx: c_int, y: *mut f64, incy: c_int);
+    fn cblas_sscal(n: c_int, alpha: f32, x: *mut f32, incx: c_int);
+    fn cblas_dscal(n: c_int, alpha: f64, x: *mut f64, incx: c_int);
+    fn cblas_sgemv(
+        order: c_int,
+        trans: c_int,
+        m: c_int,
+        n: c_int,
+        alpha: f32,
+        a: *const f32,
+        lda: c_int,
+        x: *const f32,
+        incx: c_int,
+        beta: f32,
+        y: *mut f32,
+        incy: c_int,
+    );
+    fn cblas_dgemv(
+        order: c_int,
+        trans: c_int,
+        m: c_int,
+        n: c_int,
+        alpha: f64,
+        a: *const f64,
+        lda: c_int,
+        x: *const f64,
+        incx: c_int,
+        beta: f64,
+        y: *mut f64,
+        incy: c_int,
+    );
+    fn cblas_sgemm(
+        order: c_int,
+        trans_a: c_int,
+        trans_b: c_int,
+        m: c_int,
+        n: c_int,
+        k: c_int,
+        alpha: f32,
+        a: *const f32,
+        lda: c_int,
+        b: *const f32,
+        ldb: c_int,
+        beta: f32,
+        c: *mut f32,
+        ldc: c_int,
+    );
+    fn cblas_dgemm(
+        order: c_int,
+        trans_a: c_int,
+        trans_b: c_int,
+        m: c_int,
+        n: c_int,
+        k: c_int,
+        alpha: f64,
+        a: *const f64,
+        lda: c_int,
+        b: *const f64,
+        ldb: c_int,
+        beta: f64,
+        c: *mut f64,
+        ldc: c_int,
+    );
+}
+
+// Fortran LAPACK: every argument by reference, matrices column-major.
+#[link(name = "lapack")]
+extern "C" {
+    fn sgetrf_(
+        m: *const c_int,
+        n: *const c_int,
+        a: *mut f32,
+        lda: *const c_int,
+        ipiv: *mut c_int,
+        info: *mut c_int,
+    );
+    fn dgetrf_(
+        m: *const c_int,
+        n: *const c_int,
+        a: *mut f64,
+        lda: *const c_int,
+        ipiv: *mut c_int,
+        info: *mut c_int,
+    );
+    fn spotrf_(
+        uplo: *const c_char,
+        n: *const c_int,
+        a: *mut f32,
+        lda: *const c_int,
+        info: *mut c_int,
+    );
+    fn dpotrf_(
+        uplo: *const c_char,
+        n: *const c_int,
+        a: *mut f64,
+        lda: *const c_int,
+        info: *mut c_int,
+    );
+}
+
+/// Element types with BLAS and LAPACK routines, i.e. `f32` and `f64`.
+///
+/// Each method is a safe wrapper over the routine of the same name in the
+/// type's precision. Matrices are contiguous, row-major for the BLAS calls
+/// and column-major for the LAPACK ones, and the lengths are checked before
+/// anything is handed to the library.
+pub trait BlasScalar: SimdScalar + Float {
+    fn dot(x: &[Self], y: &[Self]) -> Self;
+
+    /// `y += alpha * x`.
+    fn axpy(alpha: Self, x: &[Self], y: &mut [Self]);
+
+    /// `x *= alpha`.
+    fn scal(alpha: Self, x: &mut [Self]);
+
+    /// `y = a * x`, for `a` with `y.len()` rows and `x.len()` columns.
+    fn gemv(a: &[Self], x: &[Self], y: &mut [Self]);
+
+    /// `c = alpha * a * b + beta * c` for an `m x k` `a` and a `k x n` `b`,
+    /// with `shape` as `(m, n, k)`.
+    fn gemm(
+        shape: (usize, usize, usize),
+        alpha: Self,
+        a: &[Self],
+        b: &[Self],
+        beta: Self,
+        c: &mut [Self],
+    );
+
+    /// LU factorization of the `n x n` matrix `a` in place, returning
+    /// LAPACK's `info`. `pivots` gets the one-based row swaps.
+    fn getrf(n: usize, a: &mut [Self], pivots: &mut [c_int]) -> c_int;
+
+    /// Cholesky factorization into the lower triangle of the `n x n` matrix
+    /// `a`, in place, returning LAPACK's `info`.
+    fn potrf(n: usize, a: &mut [Self]) -> c_int;
+}
+
+/// Converts a length to the 32-bit integers of the standard BLAS interface.
+fn int(n: usize) -> c_int {
+    c_int::try_from(n).expect("dimension does not fit in a BLAS integer")
+}
+
+macro_rules! impl_blas_scalar {
+    ($t:ty, $dot:ident, $axpy:ident, $scal:ident, $gemv:ident, $gemm:ident, $getrf:ident, $potrf:ident) => {
+        impl BlasScalar for $t {
+            fn dot(x: &[$t], y: &[$t]) -> $t {
+                assert_eq!(x.len(), y.len());
+                // SAFETY: both slices hold `n` elements at unit stride.
+                unsafe { $dot(int(x.len()), x.as_ptr(), 1, y.as_ptr(), 1) }
+            }
+
+            fn axpy(alpha: $t, x: &[$t], y: &mut [$t]) {
+                assert_eq!(x.len(), y.len());
+                // SAFETY: as for `dot`.
+                unsafe { $axpy(int(x.len()), alpha, x.as_ptr(), 1, y.as_mut_ptr(), 1) }
+            }
+
+            fn scal(alpha: $t, x: &mut [$t]) {
+                // SAFETY: as for `dot`.
+                unsafe { $scal(int(x.len()), alpha, x.as_mut_ptr(), 1) }
+            }
+
+            fn gemv(a: &[$t], x: &[$t], y: &mut [$t]) {
+                let (m, n) = (y.len(), x.len());
+                assert_eq!(a.len(), m * n);
+                // SAFETY: `a` is `m x n` and row-major with `n` columns, which
+                // the leading dimension covers; `max(1, n)` is what BLAS
+                // requires when it is empty.
+                unsafe {
+                    $gemv(
+                        ROW_MAJOR,
+                        NO_TRANS,
+                        int(m),
+                        int(n),
+                        1.,
+                        a.as_ptr(),
+                        int(n.max(1)),
+                        x.as_ptr(),
+                        1,
+                        0.,
+                        y.as_mut_ptr(),
+                        1,
+                    )
+                }
+            }
+
+            fn gemm(
+                (m, n, k): (usize, usize, usize),
+                alpha: $t,
+                a: &[$t],
+                b: &[$t],
+                beta: $t,
+                c: &mut [$t],
+            ) {
+                assert_eq!(a.len(), m * k);
+                assert_eq!(b.len(), k * n);
+                assert_eq!(c.len(), m * n);
+                // SAFETY: the three matrices are row-major with the lengths
+                // checked above and their column counts as leading dimensions.
+                unsafe {
+                    $gemm(
+                        ROW_MAJOR,
+                        NO_TRANS,
+                        NO_TRANS,
+                        int(m),
+                        int(n),
+                        int(k),
+                        alpha,
+                        a.as_ptr(),
+                        int(k.max(1)),
+                        b.as_ptr(),
+                        int(n.max(1)),
+                        beta,
+                        c.as_mut_ptr(),
+                        int(n.max(1)),
+                    )
+                }
+            }
+
+            fn getrf(n: usize, a: &mut [$t], pivots: &mut [c_int]) -> c_int {
+                assert_eq!(a.len(), n * n);
+                assert_eq!(pivots.len(), n);
+                let (n, lda) = (int(n), int(n.max(1)));
+                let mut info = 0;
+                // SAFETY: `a` is `n x n` with leading dimension `lda`, and
+                // `pivots` has room for one swap per column.
+                unsafe { $getrf(&n, &n, a.as_mut_ptr(), &lda, pivots.as_mut_ptr(), &mut info) };
+                info
+            }
+
+            fn potrf(n: usize, a: &mut [$t]) -> c_int {
+                assert_eq!(a.len(), n * n);
+                let (n, lda) = (int(n), int(n.max(1)));
+                let mut info = 0;
+                // SAFETY: as for `getrf`.
+                unsafe { $potrf(&(b'L' as c_char), &n, a.as_mut_ptr(), &lda, &mut info) };
+                info
+            }
+        }
+    };
+}
+
+impl_blas_scalar!(
+    f32,
+    cblas_sdot,
+    cblas_saxpy,
+    cblas_sscal,
+    cblas_sgemv,
+    cblas_sgemm,
+    sgetrf_,
+    spotrf_
+);
+impl_blas_scalar!(
+    f64,
+    cblas_ddot,
+    cblas_daxpy,
+    cblas_dscal,
+    cblas_dgemv,
+    cblas_dgemm,
+    dgetrf_,
+    dpotrf_
+);
+
+/// Forwards the kernels to the system BLAS, and `SimdMatrix::lu` and
+/// `SimdMatrix::cholesky` to its LAPACK, for large dense problems where a
+/// tuned library such as OpenBLAS beats the SIMD kernels.
+///
+/// Rows are copied into one contiguous row-major buffer for every matrix
+/// call, and back out of it. BLAS has no elementwise operations or plain
+/// sums, so those stay on [`SimdBackend`]. Results agree with the native
+/// path up to rounding, and errors are the same.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BlasBackend;
+
+impl<T: BlasScalar> Backend<T> for BlasBackend {
+    fn name(&self) -> &'static str {
+        "blas"
+    }
+
+    fn axpy(&self, alpha: T, x: &SimdVector<T>, y: &mut SimdVector<T>) {
+        T::axpy(alpha, x.as_slice(), y.as_mut_slice());
+    }
+
+    fn dot(&self, lhs: &SimdVector<T>, rhs: &SimdVector<T>) -> T {
+        T::dot(lhs.as_slice(), rhs.as_slice())
+    }
+
+    fn elementwise(&self, op: ElementwiseOp, lhs: &mut SimdVector<T>, rhs: &SimdVector<T>) {
+        SimdBackend.elementwise(op, lhs, rhs);
+    }
+
+    fn scale(&self, alpha: T, x: &mut SimdVector<T>) {
+        T::scal(alpha, x.as_mut_slice());
+    }
+
+    /// `asum` adds magnitudes, so this is `SimdBackend::sum`.
+    fn sum(&self, x: &SimdVector<T>) -> T {
+        SimdBackend.sum(x)
+    }
+
+    fn gemm(&self, alpha: T, a: &SimdMatrix<T>, b: &SimdMatrix<T>, beta: T, c: &mut SimdMatrix<T>) {
+        let (m, k) = a.dimensions();
+        let n = b.row_size;
+        // BLAS does not read `c` when `beta == 0`, so there is nothing to
+        // copy over.
+        let mut packed = if beta == T::ZERO {
+            vec![T::ZERO; m * n]
+        } else {
+            pack(c)
+        };
+        T::gemm((m, n, k), alpha, &pack(a), &pack(b), beta, &mut packed);
+        *c = SimdMatrix::from_row_slice(m, n, &packed).unwrap();
+    }
+
+    fn gemv(&self, a: &SimdMatrix<T>, x: &SimdVector<T>) -> SimdVector<T> {
+        let mut y = SimdVector::zeros(a.height());
+        T::gemv(&pack(a), x.as_slice(), y.as_mut_slice());
+        y
+    }
+
+    /// `SimdMatrix::lu` through LAPACK's `getrf`.
+    ///
+    /// `getrf` picks the same pivots, the first largest element of each
+    /// column, so the permutation matches the native one. It only stops at
+    /// exact zeros, so the diagonal of `U` is checked against the native
+    /// tolerance afterwards and a near singular matrix gives the same
+    /// `LuError::Singular`.
+    fn lu(&self, a: &SimdMatrix<T>) -> Result<LU<T>, LuError> {
+        let size = a.row_size;
+        let tolerance = a.singular_tolerance();
+
+        // The transpose in row-major order is `A` in column-major order.
+        let mut packed = pack(&a.transpose());
+        let mut pivots = vec![0; size];
+        // A positive `info` is an exact zero pivot, which the check below
+        // reports. A negative one names an argument LAPACK rejected, and
+        // leaves `pivots` unset.
+        let info = T::getrf(size, &mut packed, &mut pivots);
+        assert!(info >= 0, "getrf rejected argument {}", -info);
+        let at = |i: usize, j: usize| packed[j * size + i];
+        if let Some(pivot) = (0..size).find(|&k| at(k, k).is_nan() || at(k, k).abs() <= tolerance) {
+            return Err(LuError::Singular { pivot });
+        }
+
+        let mut permutation: Vec<usize> = (0..size).collect();
+        let mut swaps = 0;
+        for (k, &pivot) in pivots.iter().enumerate() {
+            // LAPACK counts rows from one.
+            let pivot = usize::try_from(pivot)
+                .ok()
+                .and_then(|i| i.checked_sub(1))
+                .filter(|&i| i < size)
+                .unwrap_or_else(|| {
+                    panic!("getrf returned pivot {pivot} for a {size}x{size} matrix")
+                });
+            if pivot != k {
+                permutation.swap(pivot, k);
+                swaps += 1;
+            }
+        }
+        Ok(LU {
+            lower: SimdMatrix::from_fn(size, size, |i, j| if j < i { at(i, j) } else { T::ZERO }),
+            upper: SimdMatrix::from_fn(size, size, |i, j| if j >= i { at(i, j) } else { T::ZERO }),
+            permutation,
+            swaps,
+        })
+    }
+
+    /// `SimdMatrix::cholesky` through LAPACK's `potrf`, reading only the
+    /// upper triangle like the native version. A pivot within the native
+    /// tolerance of zero gives the same `CholeskyError::NotPositiveDefinite`.
+    fn cholesky(&self, a: &SimdMatrix<T>) -> Result<Cholesky<T>, CholeskyError> {
+        let size = a.row_size;
+        let tolerance = a.pivot_tolerance();
+
+        // Read column-major, the row-major buffer is `Aᵀ`: its lower triangle
+        // is the upper triangle of `A`, and the `L` that `potrf` leaves there
+        // reads back row-major as `Lᵀ`, the layout `Cholesky` stores.
+        let mut packed = pack(a);
+        let info = T::potrf(size, &mut packed);
+        assert!(info >= 0, "potrf rejected argument {}", -info);
+        let factored = if info > 0 { info as usize - 1 } else { size };
+        // The pivots are the squares of the diagonal of `L`.
+        let pivot = (0..factored)
+            .find(|&k| packed[k * size + k] * packed[k * size + k] <= tolerance)
+            .or((factored < size).then_some(factored));
+        if let Some(pivot) = pivot {
+            return Err(CholeskyError::NotPositiveDefinite { pivot });
+        }
+
+        Ok(Cholesky {
+            upper: SimdMatrix::from_fn(size, size, |i, j| {
+                if j >= i {
+                    packed[i * size + j]
+                } else {
+                    T::ZERO
+                }
+            }),
+        })
+    }
+}
+
+/// The rows of `matrix` in one contiguous row-major buffer.
+fn pack<T: SimdScalar>(matrix: &SimdMatrix<T>) -> Vec<T> {
+    matrix
+        .matrix
+        .iter()
+        .flat_map(SimdVector::as_slice)
+        .copied()
+        .collect()
+}
@@ -0,0 +1,125 @@
+use std::{env, fmt, str::FromStr, sync::OnceLock};
+
+/// Environment variable that forces a [`CpuLevel`], e.g. `CLIFF_CPU_LEVEL=avx2`.
+pub const CPU_LEVEL_VAR: &str = "CLIFF_CPU_LEVEL";
+
+/// Instruction set levels the hot kernels are compiled for. Each kernel is
+/// built once per level, and the best one the CPU supports is picked at
+/// runtime.
+///
+/// Every level runs the same operations in the same order and none of them
+/// contracts `a * b + c` into a fused multiply-add, so results are identical
+/// across levels; forcing one is about reproducing performance, or ruling out
+/// the dispatch when chasing a bug.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CpuLevel {
+    /// Whatever the crate was compiled for, i.e. SSE2 on a default x86-64
+    /// build.
+    Baseline,
+    /// AVX2 and FMA.
+    Avx2,
+    /// AVX-512 Foundation.
+    Avx512,
+}
+
+impl CpuLevel {
+    /// The best level this CPU supports, detected once through CPUID.
+    pub fn detected() -> CpuLevel {
+        static DETECTED: OnceLock<CpuLevel> = OnceLock::new();
+        *DETECTED.get_or_init(|| {
+            #[cfg(target_arch = "x86_64")]
+            {
+                if is_x86_feature_detected!("avx512f") {
+                    return CpuLevel::Avx512;
+                }
+                if is_x86_feature_detected!("avx2") && is_x86_feature_detected!("fma") {
+                    return CpuLevel::Avx2;
+                }
+            }
+            CpuLevel::Baseline
+        })
+    }
+
+    /// The level the kernels run at: the detected one, unless
+    /// [`CPU_LEVEL_VAR`] asks for another. A forced level the CPU lacks is
+    /// lowered to the detected one, and an unrecognised value is ignored.
+    /// The variable is read once, on first use.
+    pub fn current() -> CpuLevel {
+        static CURRENT: OnceLock<CpuLevel> = OnceLock::new();
+        *CURRENT.get_or_init(|| {
+            let forced = env::var(CPU_LEVEL_VAR).ok().and_then(|i| i.parse().ok());
+            forced.map_or(Self::detected(), |i: CpuLevel| i.min(Self::detected()))
+        })
+    }
+}
+
+impl fmt::Display for CpuLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            CpuLevel::Baseline => "baseline",
+            CpuLevel::Avx2 => "avx2",
+            CpuLevel::Avx512 => "avx512",
+        })
+    }
+}
+
+impl FromStr for CpuLevel {
+    type Err = String;
+
+    /// Parses the names `Display` writes, ignoring case. `sse2` is accepted
+    /// for the baseline.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "baseline" | "sse2" => Ok(CpuLevel::Baseline),
+            "avx2" => Ok(CpuLevel::Avx2),
+            "avx512" => Ok(CpuLevel::Avx512),
+            _ => Err(format!(
+                "unknown CPU level {s:?}, expected baseline, avx2 or avx512"
+            )),
+        }
+    }
+}
+
+/// Defines `fn $name<T: SimdScalar>(level: CpuLevel, args..)` that runs
+/// `body` compiled for `level`, or for the best level the CPU has if that is
+/// lower. The body is inlined into one `#[target_feature]` copy per level, so
+/// everything it calls should be `#[inline]`.
+macro_rules! multiversion {
+    (
+        $(#[$attr:meta])*
+        $vis:vis fn $name:ident<T: SimdScalar>($($arg:ident: $ty:ty),* $(,)?) $(-> $ret:ty)?
+        $body:block
+    ) => {
+        $(#[$attr])*
+        $vis fn $name<T: SimdScalar>(
+            level: $crate::backend::CpuLevel,
+            $($arg: $ty),*
+        ) $(-> $ret)? {
+            #[inline(always)]
+            fn kernel<T: SimdScalar>($($arg: $ty),*) $(-> $ret)? $body
+
+            #[cfg(target_arch = "x86_64")]
+            #[target_feature(enable = "avx2,fma")]
+            unsafe fn avx2<T: SimdScalar>($($arg: $ty),*) $(-> $ret)? {
+                kernel($($arg),*)
+            }
+
+            #[cfg(target_arch = "x86_64")]
+            #[target_feature(enable = "avx512f")]
+            unsafe fn avx512<T: SimdScalar>($($arg: $ty),*) $(-> $ret)? {
+                kernel($($arg),*)
+            }
+
+            match level.min($crate::backend::CpuLevel::detected()) {
+                // SAFETY: the level is capped at what CPUID reported.
+                #[cfg(target_arch = "x86_64")]
+                $crate::backend::CpuLevel::Avx512 => unsafe { avx512($($arg),*) },
+                #[cfg(target_arch = "x86_64")]
+                $crate::backend::CpuLevel::Avx2 => unsafe { avx2($($arg),*) },
+                _ => kernel($($arg),*),
+            }
+        }
+    };
+}
+
+pub(crate) use multiversion;
@@ -0,0 +1,154 @@
+//! Pluggable compute backends.
+//!
+//! Every `SimdVector` and `SimdMatrix` operation that does real arithmetic
+//! bottoms out in one of the primitive kernels of [`Backend`]: axpy, dot,
+//! the elementwise operations, scaling, sums, GEMM and GEMV. The methods check
+//! dimensions, then hand the kernel to the backend that is current on the
+//! calling thread, which is [`SimdBackend`] unless [`scoped`] installed
+//! another one. Everything built on top, including the `Vector` and `Matrix`
+//! traits, follows the selected backend without changes. Operations that
+//! spread rows over rayon's pool look the backend up once on the calling
+//! thread and pass it to the pool, so every row runs on the same backend.
+//!
+//! `SimdMatrix::lu` and `SimdMatrix::cholesky` are dispatched whole, so a
+//! backend can replace them; by default they are the SIMD factorizations,
+//! running their row updates on the backend's own kernels. QR, SVD and the
+//! eigen decompositions have no such hook and only reach the backend through
+//! their kernels. With the `blas` feature, [`BlasBackend`] hands the kernels
+//! and both factorizations to the system BLAS and LAPACK.
+//!
+//! [`ScalarBackend`] is a plain loop reference for the others, to check them
+//! against and to measure what the SIMD kernels buy.
+//!
+//! A backend can also be called directly for a single operation, e.g.
+//! `SimdBackend.dot(&x, &y)`, in which case checking the shapes is up to the
+//! caller.
+
+#[cfg(feature = "blas")]
+mod blas;
+pub(crate) mod cpu;
+mod opencl;
+mod scalar;
+pub(crate) mod simd;
+
+use std::{any::Any, cell::RefCell, rc::Rc, sync::Arc};
+
+use num::Float;
+
+use crate::{
+    algebra::{
+        cholesky::{Cholesky, CholeskyError},
+        lu::{LuError, LU},
+    },
+    structures::{matrix_simd::SimdMatrix, scalar::SimdScalar, vector_simd::SimdVector},
+};
+
+#[cfg(feature = "blas")]
+pub use blas::{BlasBackend, BlasScalar};
+pub use cpu::{CpuLevel, CPU_LEVEL_VAR};
+pub use opencl::OpenClBackend;
+pub use scalar::ScalarBackend;
+pub use simd::SimdBackend;
+
+/// The elementwise operations between two vectors of equal length.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ElementwiseOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+/// The primitive kernels the vector and matrix types are built from.
+///
+/// Implementations may assume the operands have compatible shapes: the
+/// `SimdVector` and `SimdMatrix` methods check them before dispatching. They
+/// are called from rayon's pool threads, hence `Send + Sync`.
+pub trait Backend<T: SimdScalar>: Send + Sync {
+    /// Short name for logs and benchmarks.
+    fn name(&self) -> &'static str;
+
+    /// `y += alpha * x`.
+    fn axpy(&self, alpha: T, x: &SimdVector<T>, y: &mut SimdVector<T>);
+
+    fn dot(&self, lhs: &SimdVector<T>, rhs: &SimdVector<T>) -> T;
+
+    /// `lhs = lhs op rhs`, elementwise.
+    fn elementwise(&self, op: ElementwiseOp, lhs: &mut SimdVector<T>, rhs: &SimdVector<T>);
+
+    /// `x *= alpha`.
+    fn scale(&self, alpha: T, x: &mut SimdVector<T>);
+
+    fn sum(&self, x: &SimdVector<T>) -> T;
+
+    /// `c = alpha * a * b + beta * c`. With `beta == 0` the old contents of
+    /// `c` are ignored, NaNs included.
+    fn gemm(&self, alpha: T, a: &SimdMatrix<T>, b: &SimdMatrix<T>, beta: T, c: &mut SimdMatrix<T>);
+
+    /// `a * x`.
+    fn gemv(&self, a: &SimdMatrix<T>, x: &SimdVector<T>) -> SimdVector<T>;
+
+    /// `SimdMatrix::lu` of a square `a`, with the same pivoting, tolerance
+    /// and errors.
+    fn lu(&self, a: &SimdMatrix<T>) -> Result<LU<T>, LuError>
+    where
+        T: Float,
+    {
+        a.simd_lu(self)
+    }
+
+    /// `SimdMatrix::cholesky` of a square `a`, reading only its upper
+    /// triangle, with the same tolerance and errors.
+    fn cholesky(&self, a: &SimdMatrix<T>) -> Result<Cholesky<T>, CholeskyError>
+    where
+        T: Float,
+    {
+        a.simd_cholesky(self)
+    }
+}
+
+thread_local! {
+    /// Backends installed by `scoped` on this thread, innermost last. Each
+    /// entry is an `Arc<dyn Backend<T>>` for some element type `T`.
+    static SCOPED: RefCell<Vec<Rc<dyn Any>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Runs `f` with `backend` handling every `T` kernel called from this thread,
+/// then restores the previous backend, even if `f` panics. Scopes nest, and
+/// only affect element type `T`.
+///
+/// The selection is per thread, but the parallel matrix operations take the
+/// backend along to the pool threads they run on.
+pub fn scoped<T: SimdScalar, R>(backend: Arc<dyn Backend<T>>, f: impl FnOnce() -> R) -> R {
+    struct Pop;
+
+    impl Drop for Pop {
+        fn drop(&mut self) {
+            SCOPED.with(|i| i.borrow_mut().pop());
+        }
+    }
+
+    SCOPED.with(|i| i.borrow_mut().push(Rc::new(backend)));
+    let _pop = Pop;
+    f()
+}
+
+/// Calls `f` with the backend for `T` that is current on this thread.
+pub(crate) fn with_current<T: SimdScalar, R>(f: impl FnOnce(&dyn Backend<T>) -> R) -> R {
+    // The lookup ends before `f` runs, so kernels may enter nested scopes.
+    let backend = SCOPED.with(|i| {
+        i.borrow()
+            .iter()
+            .rev()
+            .find_map(|j| j.downcast_ref::<Arc<dyn Backend<T>>>().cloned())
+    });
+    match backend {
+        Some(backend) => f(backend.as_ref()),
+        None => f(&SimdBackend),
+    }
+}
+
+/// Name of the backend for `T` that is current on this thread.
+pub fn current_name<T: SimdScalar>() -> &'static str {
+    with_current::<T, _>(|i| i.name())
+}
@@ -0,0 +1,163 @@
+use std::sync::Mutex;
+
+use crate::{
+    opencl::{ClBackendError, ClContext, ClScalar},
+    structures::{matrix_simd::SimdMatrix, vector_simd::SimdVector},
+};
+
+use super::{Backend, ElementwiseOp, SimdBackend};
+
+/// Runs each kernel on an OpenCL device, copying the operands over and the
+/// result back on every call.
+///
+/// The per-call transfers make this a backend for large operands, or for
+/// comparing a device against the SIMD kernels; chains of operations that
+/// should stay on the device are better written against `DeviceVector` and
+/// `DeviceMatrix` directly. When a call fails on the device it falls back to
+/// [`SimdBackend`], and the error is kept for [`OpenClBackend::take_error`].
+pub struct OpenClBackend {
+    context: ClContext,
+    error: Mutex<Option<ClBackendError>>,
+}
+
+impl OpenClBackend {
+    pub fn new(context: ClContext) -> OpenClBackend {
+        OpenClBackend {
+            context,
+            error: Mutex::new(None),
+        }
+    }
+
+    pub fn context(&self) -> &ClContext {
+        &self.context
+    }
+
+    /// The latest device error that caused a fallback, if any, clearing it.
+    pub fn take_error(&self) -> Option<ClBackendError> {
+        self.error.lock().unwrap().take()
+    }
+
+    /// Keeps `result`'s value, or records its error and runs `fallback`.
+    fn or_fallback<R>(&self, result: Result<R, ClBackendError>, fallback: impl FnOnce() -> R) -> R {
+        result.unwrap_or_else(|e| {
+            *self.error.lock().unwrap() = Some(e);
+            fallback()
+        })
+    }
+
+    /// Overwrites `target` with `result`'s value, or records its error and
+    /// runs `fallback` on `target` instead.
+    fn assign_or_fallback<V>(
+        &self,
+        target: &mut V,
+        result: Result<V, ClBackendError>,
+        fallback: impl FnOnce(&mut V),
+    ) {
+        match result {
+            Ok(value) => *target = value,
+            Err(e) => {
+                *self.error.lock().unwrap() = Some(e);
+                fallback(target);
+            }
+        }
+    }
+
+    fn device_axpy<T: ClScalar>(
+        &self,
+        alpha: T,
+        x: &SimdVector<T>,
+        y: &SimdVector<T>,
+    ) -> Result<SimdVector<T>, ClBackendError> {
+        let mut y = y.to_device(&self.context)?;
+        y.axpy(alpha, &x.to_device(&self.context)?)?;
+        y.to_host()
+    }
+
+    fn device_elementwise<T: ClScalar>(
+        &self,
+        op: ElementwiseOp,
+        lhs: &SimdVector<T>,
+        rhs: &SimdVector<T>,
+    ) -> Result<SimdVector<T>, ClBackendError> {
+        let lhs = lhs.to_device(&self.context)?;
+        let rhs = rhs.to_device(&self.context)?;
+        match op {
+            ElementwiseOp::Add => lhs.checked_add(&rhs),
+            ElementwiseOp::Sub => lhs.checked_sub(&rhs),
+            ElementwiseOp::Mul => lhs.checked_mul(&rhs),
+            ElementwiseOp::Div => lhs.checked_div(&rhs),
+        }?
+        .to_host()
+    }
+
+    fn device_product<T: ClScalar>(
+        &self,
+        a: &SimdMatrix<T>,
+        b: &SimdMatrix<T>,
+    ) -> Result<SimdMatrix<T>, ClBackendError> {
+        a.to_device(&self.context)?
+            .try_mul(&b.to_device(&self.context)?)?
+            .to_host()
+    }
+}
+
+impl<T: ClScalar> Backend<T> for OpenClBackend {
+    fn name(&self) -> &'static str {
+        "opencl"
+    }
+
+    fn axpy(&self, alpha: T, x: &SimdVector<T>, y: &mut SimdVector<T>) {
+        let result = self.device_axpy(alpha, x, y);
+        self.assign_or_fallback(y, result, |y| SimdBackend.axpy(alpha, x, y));
+    }
+
+    fn dot(&self, lhs: &SimdVector<T>, rhs: &SimdVector<T>) -> T {
+        let result = lhs
+            .to_device(&self.context)
+            .and_then(|i| i.checked_dot(&rhs.to_device(&self.context)?));
+        self.or_fallback(result, || SimdBackend.dot(lhs, rhs))
+    }
+
+    fn elementwise(&self, op: ElementwiseOp, lhs: &mut SimdVector<T>, rhs: &SimdVector<T>) {
+        let result = self.device_elementwise(op, lhs, rhs);
+        self.assign_or_fallback(lhs, result, |lhs| SimdBackend.elementwise(op, lhs, rhs));
+    }
+
+    fn scale(&self, alpha: T, x: &mut SimdVector<T>) {
+        let result = x
+            .to_device(&self.context)
+            .and_then(|i| i.scale(alpha)?.to_host());
+        self.assign_or_fallback(x, result, |x| SimdBackend.scale(alpha, x));
+    }
+
+    fn sum(&self, x: &SimdVector<T>) -> T {
+        let result = x.to_device(&self.context).and_then(|i| i.sum());
+        self.or_fallback(result, || SimdBackend.sum(x))
+    }
+
+    /// The device computes `a * b`; scaling and accumulating into `c` happen
+    /// on the host with the SIMD kernels.
+    fn gemm(&self, alpha: T, a: &SimdMatrix<T>, b: &SimdMatrix<T>, beta: T, c: &mut SimdMatrix<T>) {
+        let result = self.device_product(a, b).map(|product| {
+            let mut out = c.clone();
+            for (row, product) in out.matrix.iter_mut().zip(product.matrix) {
+                if beta == T::ZERO {
+                    *row = product;
+                    SimdBackend.scale(alpha, row);
+                } else {
+                    SimdBackend.scale(beta, row);
+                    SimdBackend.axpy(alpha, &product, row);
+                }
+            }
+            out
+        });
+        self.assign_or_fallback(c, result, |c| SimdBackend.gemm(alpha, a, b, beta, c));
+    }
+
+    fn gemv(&self, a: &SimdMatrix<T>, x: &SimdVector<T>) -> SimdVector<T> {
+        let result = a
+            .to_device(&self.context)
+            .and_then(|i| i.try_mul_vec(&x.to_device(&self.context)?)?.to_host());
+        self.or_fallback(result, || SimdBackend.gemv(a, x))
+    }
+}
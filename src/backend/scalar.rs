@@ -0,0 +1,73 @@
+use crate::structures::{matrix_simd::SimdMatrix, scalar::SimdScalar, vector_simd::SimdVector};
+
+use super::{Backend, ElementwiseOp};
+
+/// Every kernel as a single threaded loop over the elements, one at a time.
+///
+/// Slow on purpose: it is the straightforward definition of each kernel, to
+/// compare the other backends against and to measure what the SIMD chunks and
+/// the thread pool buy. Sums are accumulated left to right, so results can
+/// differ from [`SimdBackend`](super::SimdBackend) in the last bits.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ScalarBackend;
+
+impl<T: SimdScalar> Backend<T> for ScalarBackend {
+    fn name(&self) -> &'static str {
+        "scalar"
+    }
+
+    fn axpy(&self, alpha: T, x: &SimdVector<T>, y: &mut SimdVector<T>) {
+        for (y, &x) in y.as_mut_slice().iter_mut().zip(x.as_slice()) {
+            *y = *y + alpha * x;
+        }
+    }
+
+    fn dot(&self, lhs: &SimdVector<T>, rhs: &SimdVector<T>) -> T {
+        lhs.as_slice()
+            .iter()
+            .zip(rhs.as_slice())
+            .fold(T::ZERO, |acc, (&i, &j)| acc + i * j)
+    }
+
+    fn elementwise(&self, op: ElementwiseOp, lhs: &mut SimdVector<T>, rhs: &SimdVector<T>) {
+        for (i, &j) in lhs.as_mut_slice().iter_mut().zip(rhs.as_slice()) {
+            *i = match op {
+                ElementwiseOp::Add => *i + j,
+                ElementwiseOp::Sub => *i - j,
+                ElementwiseOp::Mul => *i * j,
+                ElementwiseOp::Div => *i / j,
+            };
+        }
+    }
+
+    fn scale(&self, alpha: T, x: &mut SimdVector<T>) {
+        for i in x.as_mut_slice() {
+            *i = *i * alpha;
+        }
+    }
+
+    fn sum(&self, x: &SimdVector<T>) -> T {
+        x.as_slice().iter().fold(T::ZERO, |acc, &i| acc + i)
+    }
+
+    fn gemm(&self, alpha: T, a: &SimdMatrix<T>, b: &SimdMatrix<T>, beta: T, c: &mut SimdMatrix<T>) {
+        for (c, a) in c.matrix.iter_mut().zip(&a.matrix) {
+            for (j, c) in c.as_mut_slice().iter_mut().enumerate() {
+                let product = a
+                    .as_slice()
+                    .iter()
+                    .zip(&b.matrix)
+                    .fold(T::ZERO, |acc, (&i, b)| acc + i * b[j]);
+                *c = if beta == T::ZERO {
+                    alpha * product
+                } else {
+                    alpha * product + beta * *c
+                };
+            }
+        }
+    }
+
+    fn gemv(&self, a: &SimdMatrix<T>, x: &SimdVector<T>) -> SimdVector<T> {
+        SimdVector::from_vector(a.matrix.iter().map(|i| self.dot(i, x)).collect())
+    }
+}
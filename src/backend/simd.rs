@@ -0,0 +1,113 @@
+use rayon::prelude::*;
+
+use crate::{
+    simd::Simd,
+    structures::{
+        matrix_simd::SimdMatrix,
+        scalar::SimdScalar,
+        vector_simd::{SimdVector, LANES},
+    },
+};
+
+use super::{
+    cpu::{multiversion, CpuLevel},
+    Backend, ElementwiseOp,
+};
+
+/// Chunks of `y` handed to one rayon task by `axpy`.
+const AXPY_BLOCK: usize = 256;
+
+/// The default backend: the `SimdScalar` lane kernels over whole chunks,
+/// spread across rayon's thread pool where the work is large enough to pay
+/// for it. They are `std::simd` operations with the `nightly-simd` feature
+/// and autovectorizable loops without it. Dot products, sums, axpy and the
+/// GEMM micro-kernel are compiled per [`CpuLevel`] and run at
+/// `CpuLevel::current()`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SimdBackend;
+
+impl<T: SimdScalar> Backend<T> for SimdBackend {
+    fn name(&self) -> &'static str {
+        "simd"
+    }
+
+    fn axpy(&self, alpha: T, x: &SimdVector<T>, y: &mut SimdVector<T>) {
+        let level = CpuLevel::current();
+        y.chunks_mut()
+            .par_chunks_mut(AXPY_BLOCK)
+            .zip(x.chunks().par_chunks(AXPY_BLOCK))
+            .for_each(|(y, x)| axpy_chunks(level, alpha, x, y));
+        // `alpha * 0` is NaN for an infinite `alpha`.
+        y.clear_padding();
+    }
+
+    /// Computed chunk by chunk, without an intermediate vector.
+    fn dot(&self, lhs: &SimdVector<T>, rhs: &SimdVector<T>) -> T {
+        dot_chunks(CpuLevel::current(), lhs.chunks(), rhs.chunks())
+    }
+
+    fn elementwise(&self, op: ElementwiseOp, lhs: &mut SimdVector<T>, rhs: &SimdVector<T>) {
+        match op {
+            ElementwiseOp::Add => lhs.zip_chunks_assign(rhs, T::ZERO, T::simd_add),
+            ElementwiseOp::Sub => lhs.zip_chunks_assign(rhs, T::ZERO, T::simd_sub),
+            ElementwiseOp::Mul => lhs.zip_chunks_assign(rhs, T::ZERO, T::simd_mul),
+            // Integer lanes panic on division by zero, so divide the padding
+            // by one.
+            ElementwiseOp::Div => lhs.zip_chunks_assign(rhs, T::ONE, T::simd_div),
+        }
+    }
+
+    fn scale(&self, alpha: T, x: &mut SimdVector<T>) {
+        let factor = Simd::splat(alpha);
+        x.map_chunks_assign(|i| T::simd_mul(i, factor));
+    }
+
+    fn sum(&self, x: &SimdVector<T>) -> T {
+        sum_chunks(CpuLevel::current(), x.chunks())
+    }
+
+    fn gemm(&self, alpha: T, a: &SimdMatrix<T>, b: &SimdMatrix<T>, beta: T, c: &mut SimdMatrix<T>) {
+        c.blocked_gemm(CpuLevel::current(), alpha, a, b, beta);
+    }
+
+    /// One dot product per row, with the rows split across threads.
+    fn gemv(&self, a: &SimdMatrix<T>, x: &SimdVector<T>) -> SimdVector<T> {
+        SimdVector::from_vector(a.matrix.par_iter().map(|i| self.dot(i, x)).collect())
+    }
+}
+
+multiversion! {
+    pub(crate) fn dot_chunks<T: SimdScalar>(
+        lhs: &[Simd<T, LANES>],
+        rhs: &[Simd<T, LANES>],
+    ) -> T {
+        T::simd_reduce_sum(
+            lhs.iter()
+                .zip(rhs)
+                .fold(Simd::splat(T::ZERO), |acc, (i, j)| {
+                    T::simd_add(acc, T::simd_mul(*i, *j))
+                }),
+        )
+    }
+}
+
+multiversion! {
+    pub(crate) fn sum_chunks<T: SimdScalar>(x: &[Simd<T, LANES>]) -> T {
+        T::simd_reduce_sum(x.iter().fold(Simd::splat(T::ZERO), |i, j| T::simd_add(i, *j)))
+    }
+}
+
+multiversion! {
+    /// `y += alpha * x` over whole chunks, so the padding lanes of `y` pick up
+    /// `alpha * 0`.
+    pub(crate) fn axpy_chunks<T: SimdScalar>(
+        alpha: T,
+        x: &[Simd<T, LANES>],
+        y: &mut [Simd<T, LANES>],
+    ) {
+        let alpha = Simd::splat(alpha);
+        for (y, x) in y.iter_mut().zip(x) {
+            *y = T::simd_add(*y, T::simd_mul(alpha, *x));
+        }
+    }
+}
@@ -1,19 +1,24 @@
-#![feature(slice_take)]
-#![feature(tuple_trait)]
-#![feature(let_chains)]
-#![feature(iter_collect_into)]
-#![feature(portable_simd)]
-#![feature(async_iterator)]
+#![cfg_attr(feature = "nightly-simd", feature(portable_simd))]
 pub mod algebra;
-mod opencl;
+pub mod backend;
+pub mod opencl;
+pub mod simd;
 pub mod structures;
 
 #[cfg(test)]
 mod tests {
 
     use crate::{
-        algebra::vector::{Matrix, Vector},
-        structures::{matrix_simd::SimdMatrix, vector_simd::SimdVector},
+        algebra::{
+            cholesky::CholeskyError,
+            eigen::EigenError,
+            lu::LuError,
+            vector::{Matrix, Vector},
+        },
+        structures::{
+            matrix_simd::{MatrixOpError, SimdMatrix},
+            vector_simd::{SimdVector, VectorOpError},
+        },
     };
 
     #[test]
@@ -74,7 +79,7 @@ mod tests {
             vec![88.9, 9.0, 32.1, 0.9],
             vec![2.2, 90.0, 32.2, 0.2],
         ];
-        let mut matrix = SimdMatrix::from(init_working.clone()).unwrap();
+        let matrix = SimdMatrix::from(init_working.clone()).unwrap();
         assert!(matrix.is_square());
     }
 
@@ -82,53 +87,44 @@ mod tests {
     fn check_simd_get_fns() {
         let init = vec![5.23, 3.2, 0.44, 8.9, 9.0, 5.5, 2.0, 5.0];
         let vector = SimdVector::from_vector(init.clone());
-        assert_eq!(init.get(0).as_deref(), vector.get(0).as_ref());
+        assert_eq!(init.first(), vector.get(0).as_ref());
         println!("0 passed");
-        assert_eq!(init.get(1).as_deref(), vector.get(1).as_ref());
+        assert_eq!(init.get(1), vector.get(1).as_ref());
         println!("1 passed");
-        assert_eq!(init.get(2).as_deref(), vector.get(2).as_ref());
+        assert_eq!(init.get(2), vector.get(2).as_ref());
         println!("2 passed");
-        assert_eq!(init.get(3).as_deref(), vector.get(3).as_ref());
+        assert_eq!(init.get(3), vector.get(3).as_ref());
         println!("3 passed");
-        assert_eq!(init.get(4).as_deref(), vector.get(4).as_ref());
+        assert_eq!(init.get(4), vector.get(4).as_ref());
         println!("4 passed");
-        assert_eq!(init.get(5).as_deref(), vector.get(5).as_ref());
+        assert_eq!(init.get(5), vector.get(5).as_ref());
         println!("5 passed");
-        assert_eq!(init.get(6).as_deref(), vector.get(6).as_ref());
+        assert_eq!(init.get(6), vector.get(6).as_ref());
         println!("6 passed");
-        assert_eq!(init.get(7).as_deref(), vector.get(7).as_ref());
+        assert_eq!(init.get(7), vector.get(7).as_ref());
         println!("7 passed");
 
         let mut init_large: Vec<f32> = vec![];
         init_large.resize(556, 1.1);
-        init_large.fill_with(|| rand::random());
+        init_large.fill_with(rand::random);
         let vector_large = SimdVector::from_vector(init_large.clone());
 
-        assert_eq!(init_large.get(0).as_deref(), vector_large.get(0).as_ref());
-        assert_eq!(init_large.get(45).as_deref(), vector_large.get(45).as_ref());
-        assert_eq!(
-            init_large.get(337).as_deref(),
-            vector_large.get(337).as_ref()
-        );
-        assert_eq!(
-            init_large.get(500).as_deref(),
-            vector_large.get(500).as_ref()
-        );
-        assert_eq!(
-            init_large.get(555).as_deref(),
-            vector_large.get(555).as_ref()
-        );
+        assert_eq!(init_large.first(), vector_large.get(0).as_ref());
+        assert_eq!(init_large.get(45), vector_large.get(45).as_ref());
+        assert_eq!(init_large.get(337), vector_large.get(337).as_ref());
+        assert_eq!(init_large.get(500), vector_large.get(500).as_ref());
+        assert_eq!(init_large.get(555), vector_large.get(555).as_ref());
     }
 
     #[test]
     fn check_simd_get_thourogh() {
         for i in 0..=62 {
             let mut init: Vec<f32> = vec![];
-            init.resize_with(i, || rand::random());
+            init.resize_with(i, rand::random);
             let vector = SimdVector::from_vector(init.clone());
             for j in 0..=i {
                 println!("operating on {:?}, element {}", vector, j);
-                assert_eq!(init.get(j).as_deref(), vector.get(j).as_ref());
+                assert_eq!(init.get(j), vector.get(j).as_ref());
                 println!("vector size {}, element {} passed", i, j);
             }
         }
@@ -216,8 +212,8 @@ mod tests {
     #[test]
     fn check_vector_trait_magnitude() {
         for i in 0..63 {
-            let vector = SimdVector::from_vector(
-                std::iter::repeat_with(|| rand::random()).take(i).collect(),
+            let vector = SimdVector::<f32>::from_vector(
+                std::iter::repeat_with(rand::random).take(i).collect(),
             );
             println!(
                 "input vector is {:?}, magnitude is {}",
@@ -257,4 +253,1818 @@ mod tests {
 
     #[test]
     fn check_matrix_trait_sums() {}
+
+    #[test]
+    fn check_generic_simd_vector() {
+        let init: Vec<f64> = std::iter::repeat_with(rand::random).take(77).collect();
+        let vector = SimdVector::from_vector(init.clone());
+        assert_eq!(init, vector.to_vector());
+        assert_eq!(init.get(70).copied(), vector.get(70));
+
+        let ints = SimdVector::from_vector((0..100).collect::<Vec<i32>>());
+        let bytes = SimdVector::from_vector(vec![3u8; 35]);
+        assert_eq!(ints.sum(), (0..100).sum());
+        assert_eq!(
+            (ints.clone() + ints).to_vector(),
+            (0..200).step_by(2).collect::<Vec<i32>>()
+        );
+        assert_eq!((2u8 * bytes).to_vector(), vec![6u8; 35]);
+    }
+
+    #[test]
+    fn check_simd_flat_storage() {
+        let init: Vec<f32> = std::iter::repeat_with(rand::random).take(83).collect();
+        let vector = SimdVector::from_vector(init.clone());
+
+        assert_eq!(vector.as_slice(), init.as_slice());
+        assert_eq!(&vector[17..40], &init[17..40]);
+        assert_eq!(vector[82], init[82]);
+        assert_eq!(vector.iter().collect::<Vec<f32>>(), init);
+        assert_eq!(vector.get(83), None);
+
+        let zeros = SimdVector::from_vector(vec![0.; 5]);
+        let quot = zeros.clone() / zeros;
+        assert!(quot.iter().all(f32::is_nan));
+        assert_eq!(quot.chunks().len(), 1);
+        assert!(quot.chunks()[0].as_array()[5..].iter().all(|i| *i == 0.));
+
+        let ints = SimdVector::from_vector(vec![9, 12, 15]);
+        assert_eq!(
+            (ints / SimdVector::from_vector(vec![3, 4, 5])).to_vector(),
+            vec![3, 3, 3]
+        );
+    }
+
+    #[test]
+    fn check_simd_checked_ops() {
+        let lhs = SimdVector::from_vector(vec![1., 2., 3., 4.]);
+        let rhs = SimdVector::from_vector(vec![4., 3., 2., 1.]);
+        let short = SimdVector::from_vector(vec![1., 2., 3.]);
+
+        assert_eq!(lhs.checked_add(&rhs).unwrap().to_vector(), vec![5.; 4]);
+        assert_eq!(
+            lhs.checked_sub(&rhs).unwrap().to_vector(),
+            vec![-3., -1., 1., 3.]
+        );
+        assert_eq!(
+            lhs.checked_mul(&rhs).unwrap().to_vector(),
+            vec![4., 6., 6., 4.]
+        );
+        assert_eq!(
+            lhs.checked_div(&short),
+            Err(VectorOpError::LengthMismatch { lhs: 4, rhs: 3 })
+        );
+        assert_eq!(
+            short.checked_add(&lhs),
+            Err(VectorOpError::LengthMismatch { lhs: 3, rhs: 4 })
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn check_simd_ops_length_mismatch() {
+        let _ = SimdVector::from_vector(vec![1., 2., 3.]) + SimdVector::from_vector(vec![1., 2.]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn check_vector_zip_map_length_mismatch() {
+        // Only the required methods, so `zip_map` is the trait default.
+        #[derive(Clone)]
+        struct Plain(Vec<f64>);
+
+        impl Vector<f64> for Plain {
+            fn scale(self, scalar: f64) -> Self {
+                Plain(self.0.into_iter().map(|i| i * scalar).collect())
+            }
+            fn add(self, rhs: Self) -> Self {
+                self.zip_map(rhs, |a, b| a + b)
+            }
+            fn elem_mul(self, rhs: Self) -> Self {
+                self.zip_map(rhs, |a, b| a * b)
+            }
+            fn from_vec(input: Vec<f64>) -> Self {
+                Plain(input)
+            }
+            fn to_vec(&self) -> Vec<f64> {
+                self.0.clone()
+            }
+            fn partial_sum(&self) -> f64 {
+                self.0.iter().sum()
+            }
+        }
+
+        assert_eq!(
+            Plain(vec![1., 2.]).zip_map(Plain(vec![3., 5.]), f64::max).0,
+            vec![3., 5.]
+        );
+        let _ = Plain(vec![1., 2., 3.]).zip_map(Plain(vec![1., 2.]), f64::max);
+    }
+
+    #[test]
+    fn check_simd_ref_assign_ops() {
+        let lhs = SimdVector::from_vector(vec![1., 2., 3.]);
+        let rhs = SimdVector::from_vector(vec![4., 5., 6.]);
+
+        assert_eq!((&lhs + &rhs).to_vector(), vec![5., 7., 9.]);
+        assert_eq!((&rhs - &lhs).to_vector(), vec![3., 3., 3.]);
+        assert_eq!((&lhs * &rhs).to_vector(), vec![4., 10., 18.]);
+        assert_eq!((&rhs / &lhs).to_vector(), vec![4., 2.5, 2.]);
+        assert_eq!((-&lhs).to_vector(), vec![-1., -2., -3.]);
+        assert_eq!((&lhs * 2.).to_vector(), vec![2., 4., 6.]);
+
+        let mut acc = lhs.clone();
+        acc += &rhs;
+        acc -= &lhs;
+        assert_eq!(acc, rhs);
+        acc *= 2.;
+        acc /= &rhs;
+        assert_eq!(acc.to_vector(), vec![2.; 3]);
+        acc *= f32::INFINITY;
+        assert_eq!(acc.sum(), f32::INFINITY);
+
+        let mut matrix = SimdMatrix::from(vec![vec![1., 2.], vec![3., 4.]]).unwrap();
+        let other = SimdMatrix::from(vec![vec![4., 3.], vec![2., 1.]]).unwrap();
+        assert_eq!((&matrix + &other).to_vector(), vec![vec![5.; 2]; 2]);
+        assert_eq!(
+            (&matrix - &other).to_vector(),
+            vec![vec![-3., -1.], vec![1., 3.]]
+        );
+        assert_eq!((-&matrix).to_vector(), vec![vec![-1., -2.], vec![-3., -4.]]);
+        matrix += &other;
+        matrix *= 2.;
+        matrix /= 5.;
+        assert_eq!(matrix.to_vector(), vec![vec![2.; 2]; 2]);
+    }
+
+    #[test]
+    fn check_matrix_std_ops() {
+        let left_matrix =
+            SimdMatrix::from(vec![vec![1., 2., 3.], vec![4., 5., 6.], vec![7., 8., 9.]]).unwrap();
+        let right_matrix =
+            SimdMatrix::from(vec![vec![3., 4.], vec![8., 9.], vec![5., 6.]]).unwrap();
+        let vector = SimdVector::from_vector(vec![1., 0., -1.]);
+
+        assert_eq!(
+            (&left_matrix * &right_matrix).to_vector(),
+            vec![vec![34., 40.], vec![82., 97.], vec![130., 154.]]
+        );
+        assert_eq!((&left_matrix * &vector).to_vector(), vec![-2., -2., -2.]);
+        assert_eq!(
+            (left_matrix.clone() + left_matrix.clone() * 2.).to_vector(),
+            (left_matrix.clone() * 3.).to_vector()
+        );
+        assert_eq!(
+            (left_matrix.clone() - left_matrix.clone()).to_vector(),
+            vec![vec![0.; 3]; 3]
+        );
+
+        assert_eq!(
+            right_matrix.try_mul(&left_matrix),
+            Err(MatrixOpError::DimensionMismatch {
+                lhs: (3, 2),
+                rhs: (3, 3)
+            })
+        );
+        assert_eq!(
+            right_matrix.try_mul_vec(&vector),
+            Err(MatrixOpError::DimensionMismatch {
+                lhs: (3, 2),
+                rhs: (3, 1)
+            })
+        );
+        assert!(left_matrix.try_add(&right_matrix).is_err());
+        assert!(left_matrix.try_sub(&left_matrix).is_ok());
+    }
+
+    #[test]
+    fn check_matrix_transpose() {
+        let init: Vec<Vec<f32>> = (0..70)
+            .map(|_| std::iter::repeat_with(rand::random).take(45).collect())
+            .collect();
+        let expected: Vec<Vec<f32>> = (0..45)
+            .map(|j| init.iter().map(|i| i[j]).collect())
+            .collect();
+        let mut matrix = SimdMatrix::from(init.clone()).unwrap();
+
+        assert_eq!(matrix.transpose().to_vector(), expected);
+        assert_eq!(
+            matrix
+                .col_iter()
+                .map(|i| i.to_vector())
+                .collect::<Vec<Vec<f32>>>(),
+            expected
+        );
+        matrix.transpose_mut();
+        assert_eq!(matrix.dimensions(), (45, 70));
+        assert_eq!(matrix.to_vector(), expected);
+
+        let square: Vec<Vec<f32>> = (0..67)
+            .map(|_| std::iter::repeat_with(rand::random).take(67).collect())
+            .collect();
+        let mut square_matrix = SimdMatrix::from(square.clone()).unwrap();
+        square_matrix.transpose_mut();
+        square_matrix.transpose_mut();
+        assert_eq!(square_matrix.to_vector(), square);
+        square_matrix.transpose_mut();
+        assert_eq!(square_matrix, SimdMatrix::from(square).unwrap().transpose());
+    }
+
+    #[test]
+    fn check_matrix_gemm() {
+        let random_matrix = |rows: usize, columns: usize| -> Vec<Vec<f64>> {
+            (0..rows)
+                .map(|_| {
+                    std::iter::repeat_with(|| rand::random::<f64>() - 0.5)
+                        .take(columns)
+                        .collect()
+                })
+                .collect()
+        };
+        // Sizes that straddle the tile, panel and micro-kernel edges.
+        for (m, k, n) in [(1, 1, 1), (3, 300, 5), (70, 261, 290), (9, 17, 33)] {
+            let a = random_matrix(m, k);
+            let b = random_matrix(k, n);
+            let c = random_matrix(m, n);
+            let expected: Vec<Vec<f64>> = (0..m)
+                .map(|i| {
+                    (0..n)
+                        .map(|j| {
+                            1.5 * (0..k).map(|l| a[i][l] * b[l][j]).sum::<f64>() - 2. * c[i][j]
+                        })
+                        .collect()
+                })
+                .collect();
+
+            let mut output = SimdMatrix::from(c).unwrap();
+            output
+                .gemm(
+                    1.5,
+                    &SimdMatrix::from(a).unwrap(),
+                    &SimdMatrix::from(b).unwrap(),
+                    -2.,
+                )
+                .unwrap();
+            for (i, j) in output.to_vector().iter().zip(expected.iter()) {
+                for (x, y) in i.iter().zip(j.iter()) {
+                    assert!((x - y).abs() < 1e-10, "{x} != {y}");
+                }
+            }
+        }
+
+        let ints = SimdMatrix::from(vec![vec![1, 2], vec![3, 4]]).unwrap();
+        let mut nans = SimdMatrix::from(vec![vec![f32::NAN; 2]; 2]).unwrap();
+        nans.gemm(
+            1.,
+            &SimdMatrix::from(vec![vec![1., 0.], vec![0., 1.]]).unwrap(),
+            &SimdMatrix::from(vec![vec![1., 2.], vec![3., 4.]]).unwrap(),
+            0.,
+        )
+        .unwrap();
+        assert_eq!(nans.to_vector(), vec![vec![1., 2.], vec![3., 4.]]);
+        assert_eq!((&ints * &ints).to_vector(), vec![vec![7, 10], vec![15, 22]]);
+        assert!(ints.clone().gemm(1, &ints, &ints.transpose(), 0).is_ok());
+        assert!(SimdMatrix::from(vec![vec![0; 3]; 2])
+            .unwrap()
+            .gemm(1, &ints, &ints, 0)
+            .is_err());
+    }
+
+    #[test]
+    fn check_matrix_trait_gemv() {
+        let matrix = SimdMatrix::from(vec![vec![1., 2., 3.], vec![4., 5., 6.]]).unwrap();
+        let right = SimdVector::from_vector(vec![1., 0., 2.]);
+        let left = SimdVector::from_vector(vec![-1., 1.]);
+
+        assert_eq!(matrix.mul_vec(&right).unwrap().to_vector(), vec![7., 16.]);
+        assert_eq!(matrix.vec_mul(&left).unwrap().to_vector(), vec![3., 3., 3.]);
+        assert_eq!((&left * &matrix).to_vector(), vec![3., 3., 3.]);
+        assert_eq!(
+            matrix.mul_vec(&left),
+            Err(MatrixOpError::DimensionMismatch {
+                lhs: (2, 3),
+                rhs: (2, 1)
+            })
+        );
+        assert_eq!(
+            matrix.vec_mul(&right),
+            Err(MatrixOpError::DimensionMismatch {
+                lhs: (1, 3),
+                rhs: (2, 3)
+            })
+        );
+
+        let large: Vec<Vec<f64>> = (0..90)
+            .map(|i| (0..130).map(|j| ((i * j) % 7) as f64).collect())
+            .collect();
+        let large_matrix = SimdMatrix::from(large.clone()).unwrap();
+        let ones = SimdVector::from_vector(vec![1.; 130]);
+        assert_eq!(
+            large_matrix.mul_vec(&ones).unwrap().to_vector(),
+            large.iter().map(|i| i.iter().sum()).collect::<Vec<f64>>()
+        );
+        assert_eq!(
+            large_matrix
+                .vec_mul(&SimdVector::from_vector(vec![1.; 90]))
+                .unwrap(),
+            large_matrix
+                .transpose()
+                .mul_vec(&SimdVector::from_vector(vec![1.; 90]))
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn check_constructors() {
+        assert_eq!(SimdVector::<f32>::zeros(20).to_vector(), vec![0.; 20]);
+        assert_eq!(
+            SimdVector::linspace(0., 1., 5).to_vector(),
+            vec![0., 0.25, 0.5, 0.75, 1.]
+        );
+        assert_eq!(SimdVector::linspace(2., 3., 1).to_vector(), vec![2.]);
+        assert!(SimdVector::<f64>::linspace(2., 3., 0).is_empty());
+        assert_eq!(SimdVector::arange(0, 10, 3).to_vector(), vec![0, 3, 6, 9]);
+        assert_eq!(
+            SimdVector::arange(1., -1., -0.5).to_vector(),
+            vec![1., 0.5, 0., -0.5]
+        );
+        assert!(SimdVector::arange(0, 10, 0).is_empty());
+
+        assert_eq!(
+            SimdMatrix::<i32>::zeros(2, 3).to_vector(),
+            vec![vec![0; 3]; 2]
+        );
+        assert_eq!(
+            SimdMatrix::<i32>::ones(3, 2).to_vector(),
+            vec![vec![1; 2]; 3]
+        );
+        assert_eq!(
+            SimdMatrix::<f32>::identity(3).to_vector(),
+            vec![vec![1., 0., 0.], vec![0., 1., 0.], vec![0., 0., 1.]]
+        );
+        assert_eq!(
+            SimdMatrix::from_diagonal(&SimdVector::from_vector(vec![2, 3])).to_vector(),
+            vec![vec![2, 0], vec![0, 3]]
+        );
+        assert_eq!(
+            SimdMatrix::from_fn(2, 3, |i, j| (i * 10 + j) as i32).to_vector(),
+            vec![vec![0, 1, 2], vec![10, 11, 12]]
+        );
+        assert_eq!(
+            SimdMatrix::from_row_slice(2, 2, &[1, 2, 3, 4])
+                .unwrap()
+                .to_vector(),
+            vec![vec![1, 2], vec![3, 4]]
+        );
+        assert!(SimdMatrix::from_row_slice(2, 2, &[1, 2, 3]).is_err());
+
+        let random = SimdMatrix::random(40, 30, rand::distributions::Uniform::new(-1f32, 1.));
+        assert_eq!(random.dimensions(), (40, 30));
+        assert!(random
+            .to_vector()
+            .iter()
+            .flatten()
+            .all(|i| (-1. ..1.).contains(i)));
+
+        let empty = SimdMatrix::<f32>::from(vec![]).unwrap();
+        assert_eq!(empty.dimensions(), (0, 0));
+        assert_eq!(SimdMatrix::<f32>::from_simd(vec![]).unwrap(), empty);
+        assert_eq!(
+            SimdMatrix::<f32>::zeros(0, 4).transpose().dimensions(),
+            (4, 0)
+        );
+        assert_eq!(
+            (&SimdMatrix::<f32>::zeros(3, 0) * &SimdMatrix::zeros(0, 2)).to_vector(),
+            vec![vec![0.; 2]; 3]
+        );
+    }
+
+    fn max_difference(lhs: &SimdMatrix<f64>, rhs: &SimdMatrix<f64>) -> f64 {
+        assert_eq!(lhs.dimensions(), rhs.dimensions());
+        (lhs - rhs)
+            .to_vector()
+            .into_iter()
+            .flatten()
+            .fold(0., |acc, i| acc.max(i.abs()))
+    }
+
+    #[test]
+    fn check_lu() {
+        let size = 50;
+        let matrix = SimdMatrix::random(size, size, rand::distributions::Uniform::new(-1., 1.))
+            + SimdMatrix::identity(size) * 4.;
+        let lu = matrix.lu().unwrap();
+        let permuted = SimdMatrix::from_simd(
+            lu.permutation()
+                .iter()
+                .map(|&i| matrix.matrix[i].clone())
+                .collect(),
+        )
+        .unwrap();
+        assert!(max_difference(&(&lu.l() * lu.u()), &permuted) < 1e-12);
+
+        let rhs =
+            SimdVector::from_vector(std::iter::repeat_with(rand::random).take(size).collect());
+        let solution = lu.solve(&rhs).unwrap();
+        let residual = matrix.try_mul_vec(&solution).unwrap() - &rhs;
+        assert!(residual.iter().all(|i: f64| i.abs() < 1e-12));
+        assert!(max_difference(&(&matrix * &lu.inverse()), &SimdMatrix::identity(size)) < 1e-12);
+        let rhs_matrix = SimdMatrix::random(size, 3, rand::distributions::Standard);
+        let solutions = lu.solve_matrix(&rhs_matrix).unwrap();
+        assert!(max_difference(&(&matrix * &solutions), &rhs_matrix) < 1e-12);
+
+        let small =
+            SimdMatrix::from(vec![vec![0f64, 2., 1.], vec![1., 1., 0.], vec![3., 0., 1.]]).unwrap();
+        assert!((small.lu().unwrap().determinant() - -5.).abs() < 1e-12);
+        assert_eq!(
+            SimdMatrix::<f64>::identity(0).lu().unwrap().determinant(),
+            1.
+        );
+        assert_eq!(
+            SimdMatrix::from(vec![vec![1., 2.], vec![2., 4.]])
+                .unwrap()
+                .lu(),
+            Err(LuError::Singular { pivot: 1 })
+        );
+        assert_eq!(
+            SimdMatrix::<f64>::zeros(2, 3).lu(),
+            Err(LuError::NotSquare {
+                rows: 2,
+                columns: 3
+            })
+        );
+        assert!(matches!(
+            lu.solve(&SimdVector::zeros(3)),
+            Err(LuError::DimensionMismatch(_))
+        ));
+    }
+
+    #[test]
+    fn check_qr() {
+        let uniform = rand::distributions::Uniform::new(-1., 1.);
+        for (rows, columns) in [(40, 10), (5, 8), (6, 6)] {
+            let matrix = SimdMatrix::random(rows, columns, uniform);
+            let qr = matrix.qr();
+            let steps = rows.min(columns);
+            assert_eq!(qr.q_thin().dimensions(), (rows, steps));
+            assert_eq!(qr.r_thin().dimensions(), (steps, columns));
+            assert!(max_difference(&(&qr.q_thin() * &qr.r_thin()), &matrix) < 1e-12);
+            assert!(max_difference(&(&qr.q() * qr.r()), &matrix) < 1e-12);
+            let q = qr.q();
+            assert!(max_difference(&(&q.transpose() * &q), &SimdMatrix::identity(rows)) < 1e-12);
+            assert!(qr
+                .r()
+                .to_vector()
+                .iter()
+                .enumerate()
+                .all(|(i, row)| row[..i.min(columns)].iter().all(|&j| j == 0.)));
+        }
+
+        let matrix = SimdMatrix::random(30, 6, uniform);
+        let expected = SimdVector::from_vector(vec![1., -2., 3., 0.5, 0., 4.]);
+        let rhs = matrix.try_mul_vec(&expected).unwrap();
+        let fit = matrix.least_squares(&rhs).unwrap();
+        assert_eq!(fit.rank, 6);
+        assert!(fit.residual_norm < 1e-12);
+        assert!((fit.solution - &expected)
+            .iter()
+            .all(|i: f64| i.abs() < 1e-12));
+
+        let noisy = rhs + SimdVector::from_vector((0..30).map(|i| (i % 3) as f64 - 1.).collect());
+        let fit = matrix.least_squares(&noisy).unwrap();
+        let residual = matrix.try_mul_vec(&fit.solution).unwrap() - &noisy;
+        assert!(
+            (residual.checked_dot(&residual).unwrap().sqrt() - fit.residual_norm).abs() < 1e-12
+        );
+        let normal = matrix.try_vec_mul(&residual).unwrap();
+        assert!(normal.iter().all(|i: f64| i.abs() < 1e-12));
+
+        let deficient = SimdMatrix::from_fn(20, 4, |i, j| {
+            let (x, y) = (i as f64, (i * i % 7) as f64);
+            [x, y, x + y, 1.][j]
+        });
+        let rhs = SimdVector::from_vector((0..20).map(|i| 2. * i as f64 + 1.).collect());
+        let fit = deficient.least_squares(&rhs).unwrap();
+        assert_eq!(fit.rank, 3);
+        assert!(fit.residual_norm < 1e-10);
+        assert!(matches!(
+            deficient.least_squares(&SimdVector::zeros(4)),
+            Err(MatrixOpError::DimensionMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn check_cholesky() {
+        let size = 30;
+        let factor = SimdMatrix::random(size, size, rand::distributions::Uniform::new(-1., 1.));
+        let spd = &(&factor * &factor.transpose()) + &SimdMatrix::identity(size);
+        let cholesky = spd.cholesky().unwrap();
+        let l = cholesky.l();
+        assert!(max_difference(&(&l * &l.transpose()), &spd) < 1e-12);
+        assert!(l
+            .to_vector()
+            .iter()
+            .enumerate()
+            .all(|(i, row)| row[i + 1..].iter().all(|&j| j == 0.)));
+
+        let rhs =
+            SimdVector::from_vector(std::iter::repeat_with(rand::random).take(size).collect());
+        let solution = cholesky.solve(&rhs).unwrap();
+        let residual = spd.try_mul_vec(&solution).unwrap() - &rhs;
+        assert!(residual.iter().all(|i: f64| i.abs() < 1e-10));
+        assert!(max_difference(&(&spd * &cholesky.inverse()), &SimdMatrix::identity(size)) < 1e-10);
+        let determinant = spd.lu().unwrap().determinant();
+        assert!((cholesky.log_determinant() - determinant.ln()).abs() < 1e-8);
+
+        let diagonal = SimdMatrix::from_diagonal(&SimdVector::from_vector(vec![
+            4e-200, 9e-200, 1e-200, 1e-200,
+        ]));
+        assert!(
+            (diagonal.cholesky().unwrap().log_determinant() - (36f64.ln() - 800. * 10f64.ln()))
+                .abs()
+                < 1e-8
+        );
+
+        let indefinite = SimdMatrix::from(vec![vec![1., 2.], vec![2., 1.]]).unwrap();
+        assert_eq!(
+            indefinite.cholesky(),
+            Err(CholeskyError::NotPositiveDefinite { pivot: 1 })
+        );
+        assert_eq!(
+            indefinite.ldlt(),
+            Err(CholeskyError::NotPositiveSemidefinite { pivot: 1 })
+        );
+        assert_eq!(
+            SimdMatrix::<f64>::zeros(3, 2).cholesky(),
+            Err(CholeskyError::NotSquare {
+                rows: 3,
+                columns: 2
+            })
+        );
+
+        let ldlt = spd.ldlt().unwrap();
+        let l = ldlt.l();
+        let scaled = SimdMatrix::from_fn(size, size, |i, j| l.matrix[i][j] * ldlt.d()[j]);
+        assert!(max_difference(&(&scaled * &l.transpose()), &spd) < 1e-12);
+
+        let rank_one = SimdVector::from_vector(vec![1., 2., -1., 3.]);
+        let semidefinite = SimdMatrix::from_fn(4, 4, |i, j| {
+            rank_one[i] * rank_one[j] + if i == j && i < 2 { 1. } else { 0. }
+        });
+        assert!(semidefinite.cholesky().is_err());
+        let ldlt = semidefinite.ldlt().unwrap();
+        assert_eq!(ldlt.d().iter().filter(|&i| i == 0.).count(), 1);
+        let rhs = semidefinite
+            .try_mul_vec(&SimdVector::from_vector(vec![1., -1., 2., 0.5]))
+            .unwrap();
+        let solution = ldlt.solve(&rhs).unwrap();
+        let residual = semidefinite.try_mul_vec(&solution).unwrap() - &rhs;
+        assert!(residual.iter().all(|i: f64| i.abs() < 1e-10));
+    }
+
+    #[test]
+    fn check_symmetric_eigen() {
+        let size = 20;
+        let random = SimdMatrix::random(size, size, rand::distributions::Uniform::new(-1., 1.));
+        let symmetric = &random + &random.transpose();
+        let eigen = symmetric.symmetric_eigen().unwrap();
+        let vectors = &eigen.eigenvectors;
+        assert!(
+            max_difference(
+                &(&vectors.transpose() * vectors),
+                &SimdMatrix::identity(size)
+            ) < 1e-12
+        );
+        let scaled = SimdMatrix::from_fn(size, size, |i, j| {
+            vectors.matrix[i][j] * eigen.eigenvalues[j]
+        });
+        assert!(max_difference(&(&symmetric * vectors), &scaled) < 1e-12);
+        let magnitudes: Vec<f64> = eigen.eigenvalues.iter().map(f64::abs).collect();
+        assert!(magnitudes.windows(2).all(|i| i[0] >= i[1]));
+
+        let small =
+            SimdMatrix::from(vec![vec![2., 1., 0.], vec![1., 2., 0.], vec![0., 0., -5.]]).unwrap();
+        let eigen = small.symmetric_eigen().unwrap();
+        assert!(
+            (eigen.eigenvalues - SimdVector::from_vector(vec![-5., 3., 1.]))
+                .iter()
+                .all(|i: f64| i.abs() < 1e-14)
+        );
+        assert_eq!(
+            SimdMatrix::<f64>::zeros(0, 0)
+                .symmetric_eigen()
+                .unwrap()
+                .eigenvalues
+                .len(),
+            0
+        );
+
+        let mut invalid = SimdMatrix::<f64>::identity(3);
+        invalid.matrix[0][1] = f64::NAN;
+        assert!(matches!(
+            invalid.symmetric_eigen(),
+            Err(EigenError::NoConvergence { .. })
+        ));
+        assert_eq!(
+            SimdMatrix::<f64>::zeros(2, 3).symmetric_eigen(),
+            Err(EigenError::NotSquare {
+                rows: 2,
+                columns: 3
+            })
+        );
+    }
+
+    #[test]
+    fn check_svd() {
+        let uniform = rand::distributions::Uniform::new(-1., 1.);
+        let is_orthonormal = |matrix: &SimdMatrix<f64>| {
+            let size = matrix.row_size;
+            max_difference(&(&matrix.transpose() * matrix), &SimdMatrix::identity(size)) < 1e-12
+        };
+        for (rows, columns) in [(12, 5), (5, 12), (6, 6)] {
+            let matrix = SimdMatrix::random(rows, columns, uniform);
+            let steps = rows.min(columns);
+            let svd = matrix.svd().unwrap();
+            assert_eq!(svd.u.dimensions(), (rows, steps));
+            assert_eq!(svd.v_t.dimensions(), (steps, columns));
+            assert!(is_orthonormal(&svd.u) && is_orthonormal(&svd.v_t.transpose()));
+            let values = svd.singular_values.to_vector();
+            assert!(values.windows(2).all(|i| i[0] >= i[1]));
+            assert!(max_difference(&svd.truncated(steps), &matrix) < 1e-12);
+
+            let full = matrix.svd_full().unwrap();
+            assert_eq!(full.u.dimensions(), (rows, rows));
+            assert_eq!(full.v_t.dimensions(), (columns, columns));
+            assert!(is_orthonormal(&full.u) && is_orthonormal(&full.v_t));
+            assert!(max_difference(&full.truncated(steps), &matrix) < 1e-12);
+
+            let error = &matrix - &svd.truncated(2);
+            let squared_error: f64 = error.to_vector().iter().flatten().map(|i| i * i).sum();
+            let expected: f64 = values[2..].iter().map(|i| i * i).sum();
+            assert!((squared_error - expected).abs() < 1e-12);
+        }
+
+        let deficient = &SimdMatrix::random(10, 3, uniform) * &SimdMatrix::random(3, 8, uniform);
+        let svd = deficient.svd_full().unwrap();
+        assert_eq!(svd.rank(None), 3);
+        assert_eq!(svd.rank(Some(f64::INFINITY)), 0);
+        assert!(is_orthonormal(&svd.u) && is_orthonormal(&svd.v_t));
+        assert!(svd.condition_number() > 1e12);
+        let pseudo_inverse = svd.pseudo_inverse(None);
+        assert_eq!(pseudo_inverse.dimensions(), (8, 10));
+        assert!(
+            max_difference(&(&(&deficient * &pseudo_inverse) * &deficient), &deficient) < 1e-12
+        );
+        assert!(
+            max_difference(
+                &(&(&pseudo_inverse * &deficient) * &pseudo_inverse),
+                &pseudo_inverse
+            ) < 1e-10
+        );
+
+        let square = SimdMatrix::random(7, 7, uniform) + SimdMatrix::identity(7) * 3.;
+        let inverse = square.lu().unwrap().inverse();
+        assert!(max_difference(&square.svd().unwrap().pseudo_inverse(None), &inverse) < 1e-12);
+        let diagonal = SimdMatrix::from_diagonal(&SimdVector::from_vector(vec![1., -4., 2.]));
+        let svd = diagonal.svd().unwrap();
+        assert_eq!(svd.singular_values.to_vector(), vec![4., 2., 1.]);
+        assert_eq!(svd.condition_number(), 4.);
+        assert_eq!(
+            SimdMatrix::<f64>::zeros(3, 0)
+                .svd()
+                .unwrap()
+                .singular_values
+                .len(),
+            0
+        );
+        assert_eq!(SimdMatrix::<f64>::zeros(2, 2).svd().unwrap().rank(None), 0);
+    }
+
+    #[test]
+    fn check_general_eigen() {
+        use num::Complex;
+
+        let size = 12;
+        let matrix = SimdMatrix::random(size, size, rand::distributions::Uniform::new(-1., 1.));
+        let eigen = matrix.eigen().unwrap();
+        assert_eq!(eigen.eigenvalues, matrix.eigenvalues().unwrap());
+        let rows = matrix.to_vector();
+        for (value, vector) in eigen.eigenvalues.iter().zip(&eigen.eigenvectors) {
+            let norm: f64 = vector.iter().map(|i| i.norm_sqr()).sum();
+            assert!((norm - 1.).abs() < 1e-12);
+            for (row, x) in rows.iter().zip(vector) {
+                let product: Complex<f64> = row.iter().zip(vector).map(|(a, b)| b * a).sum();
+                assert!((product - value * x).norm() < 1e-10);
+            }
+        }
+        let moduli: Vec<f64> = eigen.eigenvalues.iter().map(|i| i.norm()).collect();
+        assert!(moduli.windows(2).all(|i| i[0] >= i[1]));
+        let trace: f64 = (0..size).map(|i| rows[i][i]).sum();
+        let sum: Complex<f64> = eigen.eigenvalues.iter().sum();
+        assert!((sum - trace).norm() < 1e-10);
+        let product: Complex<f64> = eigen.eigenvalues.iter().product();
+        assert!((product - matrix.lu().unwrap().determinant()).norm() < 1e-10);
+
+        let rotation = SimdMatrix::from(vec![vec![0., -2.], vec![2., 0.]]).unwrap();
+        let eigenvalues = rotation.eigenvalues().unwrap();
+        assert!((eigenvalues[0] - Complex::new(0., 2.)).norm() < 1e-14);
+        assert!((eigenvalues[1] - Complex::new(0., -2.)).norm() < 1e-14);
+        let triangular =
+            SimdMatrix::from_fn(5, 5, |i, j| if i <= j { (i + j + 1) as f64 } else { 0. });
+        let eigenvalues = triangular.eigenvalues().unwrap();
+        let expected = [9., 7., 5., 3., 1.].map(|i| Complex::new(i, 0.));
+        assert!(eigenvalues
+            .iter()
+            .zip(expected)
+            .all(|(a, b)| (a - b).norm() < 1e-12));
+        assert!(SimdMatrix::<f64>::zeros(0, 0)
+            .eigen()
+            .unwrap()
+            .eigenvalues
+            .is_empty());
+        for size in [1, 3] {
+            let eigen = SimdMatrix::<f64>::zeros(size, size).eigen().unwrap();
+            assert_eq!(eigen.eigenvalues, vec![Complex::new(0., 0.); size]);
+            for vector in &eigen.eigenvectors {
+                let norm: f64 = vector.iter().map(|i| i.norm_sqr()).sum();
+                assert!((norm - 1.).abs() < 1e-12);
+            }
+        }
+        assert_eq!(
+            SimdMatrix::<f64>::zeros(2, 3).eigenvalues(),
+            Err(EigenError::NotSquare {
+                rows: 2,
+                columns: 3
+            })
+        );
+    }
+
+    #[test]
+    fn check_complex_simd() {
+        use num::Complex;
+        use rand::{distributions::Uniform, Rng};
+
+        use crate::{
+            algebra::complex::{ComplexMatrix, ComplexVector},
+            structures::complex_simd::{ComplexSimdMatrix, ComplexSimdVector},
+        };
+
+        let mut rng = rand::thread_rng();
+        let range = Uniform::new(-1., 1.);
+        let mut random = |len: usize| -> Vec<Complex<f64>> {
+            (0..len)
+                .map(|_| Complex::new(rng.sample(range), rng.sample(range)))
+                .collect()
+        };
+        let close = |a: Complex<f64>, b: Complex<f64>| (a - b).norm() < 1e-12;
+
+        let (x, y) = (random(37), random(37));
+        let (lhs, rhs) = (
+            ComplexSimdVector::from_vector(x.clone()),
+            ComplexSimdVector::from_vector(y.clone()),
+        );
+        assert_eq!(lhs.to_vector(), x);
+        let product = (&lhs * &rhs).to_vector();
+        let sum = (&lhs + &rhs).to_vector();
+        for i in 0..x.len() {
+            assert!(close(product[i], x[i] * y[i]));
+            assert_eq!(sum[i], x[i] + y[i]);
+        }
+        let dot: Complex<f64> = x.iter().zip(&y).map(|(a, b)| a.conj() * b).sum();
+        assert!(close(lhs.clone().dot(rhs.clone()), dot));
+        assert!(close(
+            ComplexVector::conjugate(lhs.clone())
+                .elem_mul(rhs.clone())
+                .partial_sum(),
+            dot
+        ));
+        let norm = x.iter().map(|i| i.norm_sqr()).sum::<f64>().sqrt();
+        assert!((lhs.magnitude() - norm).abs() < 1e-12);
+        let factor = Complex::new(0.5, -2.);
+        for (a, b) in (&lhs * factor).to_vector().iter().zip(&x) {
+            assert!(close(*a, b * factor));
+        }
+        assert_eq!(
+            lhs.checked_mul(&ComplexSimdVector::zeros(3)),
+            Err(VectorOpError::LengthMismatch { lhs: 37, rhs: 3 })
+        );
+
+        let a: Vec<Vec<Complex<f64>>> = (0..9).map(|_| random(13)).collect();
+        let b: Vec<Vec<Complex<f64>>> = (0..13).map(|_| random(7)).collect();
+        let (left, right) = (
+            ComplexSimdMatrix::from(a.clone()).unwrap(),
+            ComplexSimdMatrix::from(b.clone()).unwrap(),
+        );
+        let product = (&left * &right).to_vector();
+        for i in 0..9 {
+            for j in 0..7 {
+                let expected: Complex<f64> = (0..13).map(|k| a[i][k] * b[k][j]).sum();
+                assert!(close(product[i][j], expected));
+            }
+        }
+        let adjoint = left.conjugate_transpose();
+        assert_eq!(adjoint.dimensions(), (13, 9));
+        assert_eq!(adjoint.row(4).unwrap().get(2), Some(a[2][4].conj()));
+        assert_eq!(ComplexMatrix::conjugate_transpose(&left), adjoint);
+        let vector = ComplexSimdVector::from_vector(random(13));
+        let through_simd = (&left * &vector).to_vector();
+        let through_trait = ComplexMatrix::mul_vec(&left, &vector).unwrap().to_vector();
+        for (i, j) in through_simd.iter().zip(&through_trait) {
+            assert!(close(*i, *j));
+        }
+        assert_eq!(
+            left.try_mul(&left),
+            Err(MatrixOpError::DimensionMismatch {
+                lhs: (9, 13),
+                rhs: (9, 13)
+            })
+        );
+    }
+
+    #[test]
+    fn check_sparse() {
+        use rand::Rng;
+
+        use crate::structures::sparse::{CooMatrix, CscMatrix, CsrMatrix, SparseError};
+
+        let mut rng = rand::thread_rng();
+        let (rows, columns) = (45, 38);
+        let dense = SimdMatrix::<f64>::from(
+            (0..rows)
+                .map(|_| {
+                    (0..columns)
+                        .map(|_| {
+                            if rng.gen_bool(0.1) {
+                                rng.gen_range(-1.0..1.0)
+                            } else {
+                                0.
+                            }
+                        })
+                        .collect()
+                })
+                .collect(),
+        )
+        .unwrap();
+        let csr = CsrMatrix::from_dense(&dense);
+        let csc = CscMatrix::from_dense(&dense);
+        assert_eq!(csr.to_dense(), dense);
+        assert_eq!(csc.to_dense(), dense);
+        assert_eq!(csr.to_csc(), csc);
+        assert_eq!(csc.to_csr(), csr);
+        assert_eq!(csr.to_coo().to_csr(), csr);
+        assert_eq!(csc.to_coo().to_csc(), csc);
+        assert_eq!(csr.transpose().to_dense(), dense.transpose());
+        assert_eq!(csc.transpose().to_dense(), dense.transpose());
+        assert_eq!(csr.get(3, 7), Some(dense.matrix[3][7]));
+        assert_eq!(csr.get(rows, 0), None);
+
+        // Long rows exercise the gathered SIMD chunks as well as the tail.
+        let wide = SimdMatrix::<f64>::random(6, 70, rand::distributions::Uniform::new(-1., 1.));
+        let x = SimdVector::from_vector((0..70).map(|i| i as f64 / 7.).collect());
+        let expected = wide.try_mul_vec(&x).unwrap();
+        let product = CsrMatrix::from_dense(&wide).try_mul_vec(&x).unwrap();
+        assert!((&product - &expected).iter().all(|i| i.abs() < 1e-12));
+
+        let x = SimdVector::from_vector((0..columns).map(|i| (i as f64).sin()).collect());
+        let y = SimdVector::from_vector((0..rows).map(|i| (i as f64).cos()).collect());
+        let close = |a: &SimdVector<f64>, b: &SimdVector<f64>| {
+            a.len() == b.len() && (a - b).iter().all(|i| i.abs() < 1e-12)
+        };
+        let expected = dense.try_mul_vec(&x).unwrap();
+        assert!(close(&csr.try_mul_vec(&x).unwrap(), &expected));
+        assert!(close(&csc.try_mul_vec(&x).unwrap(), &expected));
+        let expected = dense.try_vec_mul(&y).unwrap();
+        assert!(close(&csr.try_vec_mul(&y).unwrap(), &expected));
+        assert!(close(&csc.try_vec_mul(&y).unwrap(), &expected));
+
+        let rhs = SimdMatrix::random(columns, 20, rand::distributions::Uniform::new(-1., 1.));
+        let expected = &dense * &rhs;
+        assert!(max_difference(&csr.try_mul_dense(&rhs).unwrap(), &expected) < 1e-12);
+        assert!(max_difference(&csc.try_mul_dense(&rhs).unwrap(), &expected) < 1e-12);
+        let square = &dense.transpose() * &dense;
+        let sparse_square = csr.transpose().try_mul_sparse(&csr).unwrap();
+        assert!(max_difference(&sparse_square.to_dense(), &square) < 1e-12);
+        let sparse_square = csc.transpose().try_mul_sparse(&csc).unwrap();
+        assert!(max_difference(&sparse_square.to_dense(), &square) < 1e-12);
+        assert_eq!(
+            csr.try_mul_vec(&y),
+            Err(MatrixOpError::DimensionMismatch {
+                lhs: (rows, columns),
+                rhs: (rows, 1)
+            })
+        );
+
+        fn generic_product<M: Matrix<f64>>(matrix: &M, v: &M::Line) -> Vec<f64> {
+            let mut out = vec![];
+            for row in matrix.row_iter() {
+                out.push(row.dot(v.clone()));
+            }
+            out
+        }
+        let expected = dense.try_mul_vec(&x).unwrap();
+        for (i, j) in generic_product(&csr, &x).iter().zip(expected.iter()) {
+            assert!((i - j).abs() < 1e-12);
+        }
+        for (i, j) in generic_product(&csc, &x).iter().zip(expected.iter()) {
+            assert!((i - j).abs() < 1e-12);
+        }
+        assert_eq!(Matrix::column(&csr, 5), dense.column(5));
+        assert_eq!(Matrix::row(&csc, 5), dense.row(5));
+        assert_eq!(
+            Matrix::transpose(&csc).to_dense(),
+            Matrix::transpose(&dense)
+        );
+
+        let mut assembly = CooMatrix::new(3, 3);
+        for (i, j) in [(0, 0), (1, 1), (0, 0), (2, 1), (1, 1), (0, 0)] {
+            assembly.push(i, j, 1.).unwrap();
+        }
+        assert_eq!(assembly.nnz(), 6);
+        let assembled = assembly.to_csr();
+        assert_eq!(assembled.nnz(), 3);
+        assert_eq!(assembled.values(), [3., 2., 1.]);
+        assert_eq!(assembly.to_dense(), assembled.to_dense());
+        assert_eq!(assembly.to_csc().to_dense(), assembled.to_dense());
+        assert_eq!(
+            assembly.push(3, 0, 1.),
+            Err(SparseError::OutOfBounds {
+                row: 3,
+                column: 0,
+                rows: 3,
+                columns: 3
+            })
+        );
+        assert_eq!(
+            CsrMatrix::try_new(2, 2, vec![0, 1, 2], vec![0, 1], vec![1., 2.]),
+            Ok(CooMatrix::from_triplets(2, 2, [(0, 0, 1.), (1, 1, 2.)])
+                .unwrap()
+                .to_csr())
+        );
+        assert_eq!(
+            CsrMatrix::try_new(2, 2, vec![0, 2, 2], vec![1, 0], vec![1., 2.]),
+            Err(SparseError::UnsortedIndices { line: 0 })
+        );
+        assert_eq!(
+            CsrMatrix::<f64>::try_new(2, 2, vec![0, 1], vec![0], vec![1.]),
+            Err(SparseError::MalformedOffsets)
+        );
+        assert_eq!(
+            CscMatrix::try_new(3, 2, vec![0, 1, 1], vec![4], vec![1.]),
+            Err(SparseError::OutOfBounds {
+                row: 4,
+                column: 0,
+                rows: 3,
+                columns: 2
+            })
+        );
+    }
+
+    #[test]
+    fn check_solvers() {
+        use crate::{
+            algebra::solvers::{
+                bicgstab, conjugate_gradient, gmres, preconditioned_conjugate_gradient,
+                SolverError, SolverOptions,
+            },
+            structures::sparse::CsrMatrix,
+        };
+
+        let size = 40;
+        let uniform = rand::distributions::Uniform::new(-1., 1.);
+        let options = SolverOptions {
+            tolerance: 1e-10,
+            ..SolverOptions::default()
+        };
+        let rhs = SimdVector::from_vector((0..size).map(|i| (i as f64).sin()).collect());
+        let residual = |matrix: &SimdMatrix<f64>, x: &SimdVector<f64>| {
+            let r = &rhs - &matrix.try_mul_vec(x).unwrap();
+            r.checked_dot(&r).unwrap().sqrt() / rhs.checked_dot(&rhs).unwrap().sqrt()
+        };
+
+        let factor = SimdMatrix::random(size, size, uniform);
+        let spd = &(&factor.transpose() * &factor) + &SimdMatrix::identity(size);
+        let report = conjugate_gradient(&spd, &rhs, None, &options).unwrap();
+        assert!(report.converged);
+        assert!(residual(&spd, &report.solution) < 1e-9);
+        assert_eq!(report.residual_history.len(), report.iterations + 1);
+
+        let inverse_diagonal: Vec<f64> = (0..size).map(|i| 1. / spd.matrix[i][i]).collect();
+        let jacobi = |x: &SimdVector<f64>| {
+            SimdVector::from_vector(
+                x.iter()
+                    .zip(&inverse_diagonal)
+                    .map(|(a, b)| a * b)
+                    .collect(),
+            )
+        };
+        let preconditioned =
+            preconditioned_conjugate_gradient(&spd, &jacobi, &rhs, None, &options).unwrap();
+        assert!(preconditioned.converged);
+        assert!(residual(&spd, &preconditioned.solution) < 1e-9);
+
+        let general = &SimdMatrix::random(size, size, uniform) + &(SimdMatrix::identity(size) * 8.);
+        for report in [
+            bicgstab(&general, &rhs, None, &options).unwrap(),
+            gmres(&general, &rhs, None, &options).unwrap(),
+            gmres(
+                &general,
+                &rhs,
+                None,
+                &SolverOptions {
+                    restart: 5,
+                    ..options
+                },
+            )
+            .unwrap(),
+        ] {
+            assert!(report.converged);
+            assert!(residual(&general, &report.solution) < 1e-9);
+        }
+        let sparse = CsrMatrix::from_dense(&general);
+        let report = gmres(&sparse, &rhs, None, &options).unwrap();
+        assert!(residual(&general, &report.solution) < 1e-9);
+
+        // The 1D Laplacian as a matrix-free stencil.
+        let laplacian = |x: &SimdVector<f64>| {
+            SimdVector::from_vector(
+                (0..x.len())
+                    .map(|i| {
+                        2. * x[i] - if i > 0 { x[i - 1] } else { 0. } - x.get(i + 1).unwrap_or(0.)
+                    })
+                    .collect(),
+            )
+        };
+        let dense_laplacian = SimdMatrix::from_fn(size, size, |i, j| match i.abs_diff(j) {
+            0 => 2.,
+            1 => -1.,
+            _ => 0.,
+        });
+        let report = conjugate_gradient(&laplacian, &rhs, None, &options).unwrap();
+        assert!(report.converged && report.iterations <= size);
+        assert!(residual(&dense_laplacian, &report.solution) < 1e-9);
+        let restarted = conjugate_gradient(&laplacian, &rhs, Some(&report.solution), &options);
+        assert_eq!(restarted.unwrap().iterations, 0);
+
+        let limited = SolverOptions {
+            max_iterations: 3,
+            ..options
+        };
+        let report = conjugate_gradient(&laplacian, &rhs, None, &limited).unwrap();
+        assert!(!report.converged);
+        assert_eq!(report.iterations, 3);
+        assert_eq!(
+            conjugate_gradient(&-SimdMatrix::identity(size), &rhs, None, &options),
+            Err(SolverError::Breakdown { iteration: 0 })
+        );
+        assert_eq!(
+            gmres(&SimdMatrix::identity(3), &rhs, None, &options),
+            Err(SolverError::DimensionMismatch(
+                MatrixOpError::DimensionMismatch {
+                    lhs: (3, 3),
+                    rhs: (size, 1)
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn check_preconditioners() {
+        use crate::{
+            algebra::{
+                preconditioners::{
+                    IncompleteCholesky, IncompleteLU, Jacobi, Preconditioner, PreconditionerError,
+                    Ssor,
+                },
+                solvers::{
+                    bicgstab, conjugate_gradient, gmres, preconditioned_bicgstab,
+                    preconditioned_conjugate_gradient, preconditioned_gmres, SolverOptions,
+                },
+            },
+            structures::sparse::{CooMatrix, CsrMatrix},
+        };
+
+        // Five point stencils on a 12x12 grid, the second with a first order
+        // convection term that makes it nonsymmetric.
+        let side = 12;
+        let size = side * side;
+        let grid = |convection: f64| {
+            let mut assembly = CooMatrix::new(size, size);
+            for i in 0..side {
+                for j in 0..side {
+                    let row = i * side + j;
+                    assembly.push(row, row, 4.).unwrap();
+                    if i > 0 {
+                        assembly.push(row, row - side, -1. - convection).unwrap();
+                    }
+                    if i + 1 < side {
+                        assembly.push(row, row + side, -1. + convection).unwrap();
+                    }
+                    if j > 0 {
+                        assembly.push(row, row - 1, -1.).unwrap();
+                    }
+                    if j + 1 < side {
+                        assembly.push(row, row + 1, -1.).unwrap();
+                    }
+                }
+            }
+            assembly.to_csr()
+        };
+        let options = SolverOptions {
+            tolerance: 1e-10,
+            ..SolverOptions::default()
+        };
+        let rhs = SimdVector::from_vector((0..size).map(|i| (i as f64 * 0.3).cos()).collect());
+        let check = |matrix: &CsrMatrix<f64>, x: &SimdVector<f64>| {
+            let r = &rhs - &matrix.try_mul_vec(x).unwrap();
+            assert!(
+                r.checked_dot(&r).unwrap().sqrt() < 1e-9 * rhs.checked_dot(&rhs).unwrap().sqrt()
+            );
+        };
+
+        let laplacian = grid(0.);
+        let plain = conjugate_gradient(&laplacian, &rhs, None, &options).unwrap();
+        assert!(plain.converged);
+        let jacobi = Jacobi::from_csr(&laplacian).unwrap();
+        let ssor = Ssor::from_csr(&laplacian, 1.5).unwrap();
+        let cholesky = IncompleteCholesky::from_csr(&laplacian).unwrap();
+        assert_eq!(cholesky.l().nnz(), laplacian.lower_triangle().nnz());
+        let jacobi_report =
+            preconditioned_conjugate_gradient(&laplacian, &jacobi, &rhs, None, &options).unwrap();
+        check(&laplacian, &jacobi_report.solution);
+        for report in [
+            preconditioned_conjugate_gradient(&laplacian, &ssor, &rhs, None, &options).unwrap(),
+            preconditioned_conjugate_gradient(&laplacian, &cholesky, &rhs, None, &options).unwrap(),
+        ] {
+            assert!(report.converged);
+            assert!(report.iterations < plain.iterations);
+            check(&laplacian, &report.solution);
+        }
+
+        let convection = grid(0.4);
+        let lu = IncompleteLU::from_csr(&convection).unwrap();
+        let plain = gmres(&convection, &rhs, None, &options).unwrap();
+        let report = preconditioned_gmres(&convection, &lu, &rhs, None, &options).unwrap();
+        assert!(report.converged && report.iterations < plain.iterations);
+        check(&convection, &report.solution);
+        let plain = bicgstab(&convection, &rhs, None, &options).unwrap();
+        let report = preconditioned_bicgstab(&convection, &lu, &rhs, None, &options).unwrap();
+        assert!(report.converged && report.iterations < plain.iterations);
+        check(&convection, &report.solution);
+
+        // Without zeros there is nothing to drop, so the incomplete
+        // factorizations are exact.
+        let uniform = rand::distributions::Uniform::new(0.5, 1.);
+        let dense = &SimdMatrix::random(30, 30, uniform) + &(SimdMatrix::identity(30) * 30.);
+        let small_rhs = SimdVector::from_vector((0..30).map(|i| i as f64).collect());
+        let exact = dense.lu().unwrap().solve(&small_rhs).unwrap();
+        let applied = IncompleteLU::from_dense(&dense).unwrap().apply(&small_rhs);
+        assert!((&applied - &exact).iter().all(|i| i.abs() < 1e-12));
+        let dense = &dense.transpose() * &dense;
+        let exact = dense.cholesky().unwrap().solve(&small_rhs).unwrap();
+        let applied = IncompleteCholesky::from_dense(&dense)
+            .unwrap()
+            .apply(&small_rhs);
+        assert!((&applied - &exact).iter().all(|i| i.abs() < 1e-12));
+        assert_eq!(
+            Jacobi::from_dense(&SimdMatrix::<f64>::zeros(2, 3)),
+            Err(PreconditionerError::NotSquare {
+                rows: 2,
+                columns: 3
+            })
+        );
+        let singular = SimdMatrix::from(vec![vec![1., 2.], vec![2., 0.]]).unwrap();
+        assert_eq!(
+            IncompleteLU::from_dense(&singular),
+            Err(PreconditionerError::ZeroPivot { pivot: 1 })
+        );
+        assert_eq!(
+            Jacobi::from_dense(&singular),
+            Err(PreconditionerError::ZeroPivot { pivot: 1 })
+        );
+        let indefinite = SimdMatrix::from(vec![vec![1., 2.], vec![2., 1.]]).unwrap();
+        assert_eq!(
+            IncompleteCholesky::from_dense(&indefinite),
+            Err(PreconditionerError::NotPositiveDefinite { pivot: 1 })
+        );
+        assert_eq!(
+            Ssor::from_dense(&dense, 2.),
+            Err(PreconditionerError::InvalidRelaxation)
+        );
+    }
+
+    #[test]
+    fn check_matrix_elementwise() {
+        use crate::structures::sparse::CsrMatrix;
+
+        let lhs = SimdMatrix::<f64>::from(vec![vec![1., 2., 0.], vec![3., 4., 5.]]).unwrap();
+        let rhs = SimdMatrix::<f64>::from(vec![vec![5., 6., 7.], vec![-7., 8., 1.]]).unwrap();
+        let expect = |rows: Vec<Vec<f64>>| SimdMatrix::from(rows).unwrap();
+        let sum = expect(vec![vec![6., 8., 7.], vec![-4., 12., 6.]]);
+        let difference = expect(vec![vec![-4., -4., -7.], vec![10., -4., 4.]]);
+        let product = expect(vec![vec![5., 12., 0.], vec![-21., 32., 5.]]);
+        let quotient = expect(vec![vec![0.2, 2. / 6., 0.], vec![-3. / 7., 0.5, 5.]]);
+
+        assert_eq!(Matrix::add(lhs.clone(), rhs.clone()), Some(sum.clone()));
+        assert_eq!(
+            Matrix::sub(lhs.clone(), rhs.clone()),
+            Some(difference.clone())
+        );
+        assert_eq!(lhs.clone().hadamard(rhs.clone()), Some(product.clone()));
+        assert_eq!(
+            Matrix::elem_div(lhs.clone(), rhs.clone()),
+            Some(quotient.clone())
+        );
+        assert_eq!(lhs.try_hadamard(&rhs), Ok(product.clone()));
+        assert_eq!(lhs.try_elem_div(&rhs), Ok(quotient.clone()));
+        assert_eq!(
+            Matrix::map(&lhs, |i| i * i + 1.),
+            expect(vec![vec![2., 5., 1.], vec![10., 17., 26.]])
+        );
+        assert_eq!(
+            Matrix::zip_map(&lhs, &rhs, f64::max),
+            Some(expect(vec![vec![5., 6., 7.], vec![3., 8., 5.]]))
+        );
+        assert_eq!(
+            lhs.try_zip_map(&lhs.transpose(), f64::max),
+            Err(MatrixOpError::DimensionMismatch {
+                lhs: (2, 3),
+                rhs: (3, 2)
+            })
+        );
+        assert_eq!(Matrix::add(lhs.clone(), lhs.transpose()), None);
+
+        // The trait defaults, through a type that does not override them.
+        let (sparse_lhs, sparse_rhs) = (CsrMatrix::from_dense(&lhs), CsrMatrix::from_dense(&rhs));
+        let dense = |m: Option<CsrMatrix<f64>>| m.unwrap().to_dense();
+        assert_eq!(
+            dense(Matrix::add(sparse_lhs.clone(), sparse_rhs.clone())),
+            sum
+        );
+        assert_eq!(
+            dense(Matrix::sub(sparse_lhs.clone(), sparse_rhs.clone())),
+            difference
+        );
+        assert_eq!(
+            dense(sparse_lhs.clone().hadamard(sparse_rhs.clone())),
+            product
+        );
+        let divided = dense(Matrix::elem_div(sparse_lhs.clone(), sparse_rhs.clone()));
+        assert!(max_difference(&divided, &quotient) < 1e-15);
+        assert_eq!(
+            dense(Matrix::zip_map(&sparse_lhs, &sparse_rhs, f64::min)),
+            lhs.try_zip_map(&rhs, f64::min).unwrap()
+        );
+        assert_eq!(
+            Matrix::map(&sparse_lhs, |i| 2. * i).to_dense(),
+            lhs.map(|i| 2. * i)
+        );
+
+        let large = SimdMatrix::random(37, 21, rand::distributions::Uniform::new(1., 2.));
+        let ratio = large.try_elem_div(&large.map(|i| 2. * i)).unwrap();
+        assert!(ratio.to_vector().iter().flatten().all(|&i| i == 0.5));
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn check_async_matrix_elementwise() {
+        use crate::algebra::vector_async::{AsyncMatrix, AsyncVector};
+        use async_trait::async_trait;
+        use futures::executor::block_on;
+
+        #[derive(Clone, Debug, PartialEq)]
+        struct Row(Vec<f64>);
+
+        #[async_trait]
+        impl AsyncVector<f64> for Row {
+            async fn scale(self, scalar: f64) -> Self {
+                Row(self.0.iter().map(|i| i * scalar).collect())
+            }
+            async fn add(self, rhs: Self) -> Self {
+                Row(self.0.iter().zip(&rhs.0).map(|(a, b)| a + b).collect())
+            }
+            async fn elem_mul(self, rhs: Self) -> Self {
+                Row(self.0.iter().zip(&rhs.0).map(|(a, b)| a * b).collect())
+            }
+            async fn from_vec(input: Vec<f64>) -> Self {
+                Row(input)
+            }
+            async fn to_vec(&self) -> Vec<f64> {
+                self.0.clone()
+            }
+            async fn partial_sum(&self) -> f64 {
+                self.0.iter().sum()
+            }
+        }
+
+        // Only implements the required methods, so the defaults are tested.
+        #[derive(Clone, Copy, Debug, PartialEq)]
+        struct Grid([[f64; 3]; 2]);
+
+        #[async_trait]
+        impl AsyncMatrix<f64> for Grid {
+            type Line = Row;
+            type LineIter<'a> = std::vec::IntoIter<Row>;
+
+            async fn from_vectors(input: Vec<Row>) -> Option<Self> {
+                let rows: Vec<[f64; 3]> = input
+                    .into_iter()
+                    .map(|i| i.0.try_into().ok())
+                    .collect::<Option<_>>()?;
+                Some(Grid(rows.try_into().ok()?))
+            }
+            fn dimensions(&self) -> (usize, usize) {
+                (2, 3)
+            }
+            async fn row(&self, index: usize) -> Option<Row> {
+                self.0.get(index).map(|i| Row(i.to_vec()))
+            }
+            async fn column(&self, index: usize) -> Option<Row> {
+                (index < 3).then(|| Row(self.0.iter().map(|i| i[index]).collect()))
+            }
+            fn row_iter(&self) -> Self::LineIter<'_> {
+                self.0
+                    .iter()
+                    .map(|i| Row(i.to_vec()))
+                    .collect::<Vec<_>>()
+                    .into_iter()
+            }
+            fn col_iter(&self) -> Self::LineIter<'_> {
+                (0..3)
+                    .map(|j| Row(self.0.iter().map(|i| i[j]).collect()))
+                    .collect::<Vec<_>>()
+                    .into_iter()
+            }
+        }
+
+        let lhs = Grid([[1., 2., 0.], [3., 4., 5.]]);
+        let rhs = Grid([[5., 6., 7.], [-7., 8., 1.]]);
+        block_on(async {
+            assert_eq!(
+                lhs.add(rhs).await,
+                Some(Grid([[6., 8., 7.], [-4., 12., 6.]]))
+            );
+            assert_eq!(
+                lhs.sub(rhs).await,
+                Some(Grid([[-4., -4., -7.], [10., -4., 4.]]))
+            );
+            assert_eq!(
+                lhs.hadamard(rhs).await,
+                Some(Grid([[5., 12., 0.], [-21., 32., 5.]]))
+            );
+            assert_eq!(
+                AsyncMatrix::elem_div(lhs, rhs).await,
+                Some(Grid([[0.2, 2. / 6., 0.], [-3. / 7., 0.5, 5.]]))
+            );
+            assert_eq!(
+                AsyncMatrix::map(&lhs, |i| i * i + 1.).await,
+                Grid([[2., 5., 1.], [10., 17., 26.]])
+            );
+            assert_eq!(
+                AsyncMatrix::zip_map(&lhs, &rhs, f64::max).await,
+                Some(Grid([[5., 6., 7.], [3., 8., 5.]]))
+            );
+        });
+
+        let mismatched = std::panic::catch_unwind(|| {
+            block_on(Row(vec![1., 2.]).zip_map(Row(vec![1.]), f64::max))
+        });
+        assert!(mismatched.is_err());
+    }
+
+    #[test]
+    #[ignore = "needs an OpenCL device; CI runs it on PoCL's CPU one"]
+    fn check_opencl() {
+        use crate::{
+            backend::{self, Backend, OpenClBackend},
+            opencl::{ClBackendError, ClContext},
+        };
+        use rand::distributions::Uniform;
+        use std::sync::Arc;
+
+        let context = ClContext::new().unwrap();
+        let close = |lhs: &SimdVector<f32>, rhs: &SimdVector<f32>| {
+            lhs.len() == rhs.len()
+                && lhs
+                    .iter()
+                    .zip(rhs.iter())
+                    .all(|(i, j)| (i - j).abs() < 1e-3)
+        };
+
+        let distribution = Uniform::new(-1f32, 1.);
+        let lhs = SimdMatrix::random(1, 1000, distribution).matrix.remove(0);
+        let rhs = SimdMatrix::random(1, 1000, distribution).matrix.remove(0);
+        let lhs_device = lhs.to_device(&context).unwrap();
+        let rhs_device = rhs.to_device(&context).unwrap();
+        assert_eq!(lhs_device.to_host().unwrap(), lhs);
+
+        let sum = lhs_device.checked_add(&rhs_device).unwrap().to_host();
+        assert!(close(&sum.unwrap(), &(&lhs + &rhs)));
+        let difference = lhs_device.checked_sub(&rhs_device).unwrap().to_host();
+        assert!(close(&difference.unwrap(), &(&lhs - &rhs)));
+        let product = lhs_device.checked_mul(&rhs_device).unwrap().to_host();
+        assert!(close(&product.unwrap(), &(&lhs * &rhs)));
+        let dot = lhs_device.checked_dot(&rhs_device).unwrap();
+        assert!((dot - lhs.checked_dot(&rhs).unwrap()).abs() < 1e-3);
+        assert!((lhs_device.sum().unwrap() - lhs.sum()).abs() < 1e-3);
+
+        let mut y = rhs.to_device(&context).unwrap();
+        y.axpy(2., &lhs_device).unwrap();
+        let mut expected = rhs.clone();
+        expected.axpy(2., &lhs);
+        assert!(close(&y.to_host().unwrap(), &expected));
+        let scaled = lhs_device.checked_add(&rhs_device).unwrap().scale(3.);
+        assert!(close(
+            &scaled.unwrap().to_host().unwrap(),
+            &(&lhs + &rhs).scale(3.)
+        ));
+
+        // Odd sizes leave partial tiles on every edge of the GEMM grid.
+        let a = SimdMatrix::<f32>::random(37, 21, distribution);
+        let b = SimdMatrix::<f32>::random(21, 19, distribution);
+        let a_device = a.to_device(&context).unwrap();
+        let product = a_device
+            .try_mul(&b.to_device(&context).unwrap())
+            .unwrap()
+            .to_host()
+            .unwrap();
+        let expected = a.try_mul(&b).unwrap();
+        assert_eq!(product.dimensions(), (37, 19));
+        for (i, j) in product.matrix.iter().zip(expected.matrix.iter()) {
+            assert!(close(i, j));
+        }
+        let x = SimdMatrix::random(1, 21, distribution).matrix.remove(0);
+        let y = a_device
+            .try_mul_vec(&x.to_device(&context).unwrap())
+            .unwrap()
+            .to_host()
+            .unwrap();
+        assert!(close(&y, &a.try_mul_vec(&x).unwrap()));
+
+        assert!(matches!(
+            a_device.try_mul(&a_device),
+            Err(ClBackendError::DimensionMismatch(_))
+        ));
+        assert!(matches!(
+            lhs_device.checked_add(&y.to_device(&context).unwrap()),
+            Err(ClBackendError::LengthMismatch(_))
+        ));
+        let empty = SimdVector::<f32>::from_vector(vec![]);
+        assert_eq!(empty.to_device(&context).unwrap().sum().unwrap(), 0.);
+
+        // The same kernels behind the backend trait, through the host types.
+        let opencl = Arc::new(OpenClBackend::new(ClContext::new().unwrap()));
+        let (product, y, dot) = backend::scoped(opencl.clone() as Arc<dyn Backend<f32>>, || {
+            (
+                a.try_mul(&b).unwrap(),
+                a.try_mul_vec(&x).unwrap(),
+                lhs.checked_dot(&rhs).unwrap(),
+            )
+        });
+        assert!(opencl.take_error().is_none());
+        for (i, j) in product.matrix.iter().zip(expected.matrix.iter()) {
+            assert!(close(i, j));
+        }
+        assert!(close(&y, &a.try_mul_vec(&x).unwrap()));
+        assert!((dot - lhs.checked_dot(&rhs).unwrap()).abs() < 1e-3);
+    }
+
+    #[test]
+    fn check_backend() {
+        use crate::{
+            backend::{self, Backend, ElementwiseOp, ScalarBackend, SimdBackend},
+            structures::complex_simd::ComplexSimdVector,
+        };
+        use rand::distributions::Uniform;
+        use std::{
+            panic,
+            sync::{
+                atomic::{AtomicUsize, Ordering},
+                Arc,
+            },
+        };
+
+        // Delegates to the SIMD kernels, counting the calls it receives.
+        #[derive(Default)]
+        struct Counting(AtomicUsize);
+
+        impl Counting {
+            fn tick(&self) -> SimdBackend {
+                self.0.fetch_add(1, Ordering::Relaxed);
+                SimdBackend
+            }
+        }
+
+        impl Backend<f64> for Counting {
+            fn name(&self) -> &'static str {
+                "counting"
+            }
+            fn axpy(&self, alpha: f64, x: &SimdVector<f64>, y: &mut SimdVector<f64>) {
+                self.tick().axpy(alpha, x, y)
+            }
+            fn dot(&self, lhs: &SimdVector<f64>, rhs: &SimdVector<f64>) -> f64 {
+                self.tick().dot(lhs, rhs)
+            }
+            fn elementwise(
+                &self,
+                op: ElementwiseOp,
+                lhs: &mut SimdVector<f64>,
+                rhs: &SimdVector<f64>,
+            ) {
+                self.tick().elementwise(op, lhs, rhs)
+            }
+            fn scale(&self, alpha: f64, x: &mut SimdVector<f64>) {
+                self.tick().scale(alpha, x)
+            }
+            fn sum(&self, x: &SimdVector<f64>) -> f64 {
+                self.tick().sum(x)
+            }
+            fn gemm(
+                &self,
+                alpha: f64,
+                a: &SimdMatrix<f64>,
+                b: &SimdMatrix<f64>,
+                beta: f64,
+                c: &mut SimdMatrix<f64>,
+            ) {
+                self.tick().gemm(alpha, a, b, beta, c)
+            }
+            fn gemv(&self, a: &SimdMatrix<f64>, x: &SimdVector<f64>) -> SimdVector<f64> {
+                self.tick().gemv(a, x)
+            }
+        }
+
+        let distribution = Uniform::new(-1., 1.);
+        let a = SimdMatrix::<f64>::random(20, 30, distribution);
+        let b = SimdMatrix::<f64>::random(30, 10, distribution);
+        let x = SimdMatrix::<f64>::random(1, 30, distribution)
+            .matrix
+            .remove(0);
+        let y = SimdMatrix::<f64>::random(1, 30, distribution)
+            .matrix
+            .remove(0);
+        let z = ComplexSimdVector::from_parts(x.clone(), y.clone()).unwrap();
+        let run = || {
+            (
+                a.try_mul(&b).unwrap(),
+                a.try_mul_vec(&x).unwrap(),
+                x.checked_dot(&y).unwrap(),
+                (&x + &y).scale(2.),
+                Vector::magnitude(&x),
+                z.checked_mul(&z).unwrap(),
+            )
+        };
+        let expected = run();
+        assert_eq!(backend::current_name::<f64>(), "simd");
+
+        let counting = Arc::new(Counting::default());
+        let result = backend::scoped(counting.clone() as Arc<dyn Backend<f64>>, || {
+            assert_eq!(backend::current_name::<f64>(), "counting");
+            // Scopes only apply to their own element type, and nest.
+            assert_eq!(backend::current_name::<f32>(), "simd");
+            backend::scoped(Arc::new(SimdBackend) as Arc<dyn Backend<f64>>, || {
+                assert_eq!(backend::current_name::<f64>(), "simd");
+                x.checked_dot(&y).unwrap();
+            });
+            run()
+        });
+        assert_eq!(result, expected);
+        // gemm, gemv, dot, add, scale, then elem_mul and sum for magnitude,
+        // and four products and two sums for the complex product.
+        assert_eq!(counting.0.load(Ordering::Relaxed), 13);
+
+        run();
+        assert_eq!(counting.0.load(Ordering::Relaxed), 13);
+        // Row by row operations take the backend along to the pool threads:
+        // one elementwise call per row for `+=`, and one `axpy` per row below
+        // each pivot for `lu`.
+        let square = SimdMatrix::<f64>::random(12, 12, distribution);
+        let factored = backend::scoped(counting.clone() as Arc<dyn Backend<f64>>, || {
+            let mut sum = square.clone();
+            sum += &square;
+            (sum, square.lu())
+        });
+        assert_eq!(factored, (&square + &square, square.lu()));
+        assert_eq!(counting.0.load(Ordering::Relaxed), 13 + 12 + 66);
+        let unwound = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+            backend::scoped(counting.clone() as Arc<dyn Backend<f64>>, || panic!())
+        }));
+        assert!(unwound.is_err());
+        assert_eq!(backend::current_name::<f64>(), "simd");
+
+        assert_eq!(SimdBackend.dot(&x, &y), expected.2);
+
+        // The plain loops agree with the SIMD kernels up to rounding.
+        let scalar = backend::scoped(Arc::new(ScalarBackend) as Arc<dyn Backend<f64>>, run);
+        let close = |lhs: &SimdVector<f64>, rhs: &SimdVector<f64>| {
+            lhs.iter()
+                .zip(rhs.iter())
+                .all(|(i, j)| (i - j).abs() < 1e-12)
+        };
+        assert!(max_difference(&scalar.0, &expected.0) < 1e-12);
+        assert!(close(&scalar.1, &expected.1));
+        assert!((scalar.2 - expected.2).abs() < 1e-12);
+        assert!(close(&scalar.3, &expected.3));
+        assert!((scalar.4 - expected.4).abs() < 1e-12);
+        assert!(close(scalar.5.re(), expected.5.re()) && close(scalar.5.im(), expected.5.im()));
+        let lu = backend::scoped(Arc::new(ScalarBackend) as Arc<dyn Backend<f64>>, || {
+            square.lu().unwrap()
+        });
+        let native = square.lu().unwrap();
+        assert!(max_difference(lu.u(), native.u()) < 1e-12);
+    }
+
+    #[test]
+    fn check_lane_kernels() {
+        use crate::{simd::Simd, structures::scalar::SimdScalar};
+
+        // Both configurations wrap integer overflow and keep float lanes
+        // independent.
+        let lhs = Simd::<i8, 4>::from_array([127, -128, 3, 0]);
+        let rhs = Simd::from_array([1, -1, 4, 0]);
+        assert_eq!(i8::simd_add(lhs, rhs).to_array(), [-128, 127, 7, 0]);
+        assert_eq!(i8::simd_mul(lhs, rhs).to_array(), [127, -128, 12, 0]);
+        assert_eq!(
+            u8::simd_neg(Simd::<u8, 4>::from_array([0, 1, 2, 255])).to_array(),
+            [0, 255, 254, 1]
+        );
+        assert_eq!(i8::simd_reduce_sum(lhs), 2);
+        let floats = Simd::<f64, 4>::from_slice(&[1., -2., 0.5, 4., 99.]);
+        assert_eq!(
+            f64::simd_div(floats, Simd::splat(2.)).as_array(),
+            &[0.5, -1., 0.25, 2.]
+        );
+        assert_eq!(f64::simd_reduce_sum(floats), 3.5);
+
+        let mut padded = Simd::<f32, 4>::splat(1.);
+        padded.as_mut_array()[2..].fill(0.);
+        assert_eq!(padded.to_array(), [1., 1., 0., 0.]);
+
+        // Chunks are aligned to their size, with no padding between them.
+        use std::mem::{align_of, size_of};
+        assert_eq!(
+            (size_of::<Simd<f32, 16>>(), align_of::<Simd<f32, 16>>()),
+            (64, 64)
+        );
+        assert_eq!(
+            (size_of::<Simd<i8, 16>>(), align_of::<Simd<i8, 16>>()),
+            (16, 16)
+        );
+        assert!(align_of::<Simd<f64, 16>>() >= 64);
+        let vector = SimdVector::<f32>::zeros(40);
+        assert_eq!(vector.as_slice().as_ptr() as usize % 64, 0);
+    }
+
+    #[test]
+    fn check_cpu_dispatch() {
+        use crate::backend::{
+            simd::{axpy_chunks, dot_chunks, sum_chunks},
+            CpuLevel,
+        };
+        use rand::distributions::Uniform;
+
+        assert_eq!("AVX2".parse(), Ok(CpuLevel::Avx2));
+        assert_eq!("sse2".parse(), Ok(CpuLevel::Baseline));
+        assert!("avx9000".parse::<CpuLevel>().is_err());
+        for level in [CpuLevel::Baseline, CpuLevel::Avx2, CpuLevel::Avx512] {
+            assert_eq!(level.to_string().parse(), Ok(level));
+        }
+        assert!(CpuLevel::current() <= CpuLevel::detected());
+
+        // Levels the CPU lacks fall back to the best it has, so every level
+        // can be asked for, and all of them must agree bit for bit.
+        let distribution = Uniform::new(-1f32, 1.);
+        let x = SimdMatrix::random(1, 1001, distribution).matrix.remove(0);
+        let y = SimdMatrix::random(1, 1001, distribution).matrix.remove(0);
+        let a = SimdMatrix::random(67, 45, distribution);
+        let b = SimdMatrix::random(45, 83, distribution);
+        let run = |level| {
+            let mut axpy = y.clone();
+            axpy_chunks(level, 0.5, x.chunks(), axpy.chunks_mut());
+            let mut gemm = SimdMatrix::ones(67, 83);
+            gemm.blocked_gemm(level, 2., &a, &b, 0.5);
+            (
+                dot_chunks(level, x.chunks(), y.chunks()).to_bits(),
+                sum_chunks(level, x.chunks()).to_bits(),
+                axpy,
+                gemm,
+            )
+        };
+        let baseline = run(CpuLevel::Baseline);
+        assert_eq!(run(CpuLevel::Avx2), baseline);
+        assert_eq!(run(CpuLevel::Avx512), baseline);
+        assert_eq!(baseline.3, {
+            let mut expected = SimdMatrix::ones(67, 83);
+            expected.gemm(2., &a, &b, 0.5).unwrap();
+            expected
+        });
+    }
+
+    #[cfg(feature = "blas")]
+    #[test]
+    fn check_blas_parity() {
+        use crate::backend::{self, Backend, BlasBackend};
+        use rand::distributions::Uniform;
+        use std::sync::Arc;
+
+        fn with_blas<R>(f: impl FnOnce() -> R) -> R {
+            backend::scoped(Arc::new(BlasBackend) as Arc<dyn Backend<f64>>, f)
+        }
+
+        let distribution = Uniform::new(-1f64, 1.);
+        let x = SimdMatrix::random(1, 1001, distribution).matrix.remove(0);
+        let y = SimdMatrix::random(1, 1001, distribution).matrix.remove(0);
+        let a = SimdMatrix::random(67, 45, distribution);
+        let b = SimdMatrix::random(45, 83, distribution);
+        let run = || {
+            let mut axpy = y.clone();
+            axpy.axpy(0.5, &x);
+            let mut gemm = SimdMatrix::ones(67, 83);
+            gemm.gemm(2., &a, &b, 0.5).unwrap();
+            // `beta == 0` must not read the NaNs.
+            let mut overwrite = SimdMatrix::from_fn(67, 83, |_, _| f64::NAN);
+            overwrite.gemm(1., &a, &b, 0.).unwrap();
+            let empty = SimdMatrix::<f64>::zeros(67, 0).try_mul(&SimdMatrix::zeros(0, 83));
+            (
+                x.checked_dot(&y).unwrap(),
+                SimdMatrix::from_simd(vec![axpy, x.clone().scale(-3.), &x + &y]).unwrap(),
+                gemm,
+                overwrite,
+                empty.unwrap(),
+                SimdMatrix::from_simd(vec![a.try_mul_vec(&b.column(0).unwrap()).unwrap()]),
+            )
+        };
+        let native = run();
+        let blas = with_blas(|| {
+            assert_eq!(backend::current_name::<f64>(), "blas");
+            run()
+        });
+        assert!((native.0 - blas.0).abs() < 1e-12);
+        assert!(max_difference(&native.1, &blas.1) < 1e-12);
+        assert!(max_difference(&native.2, &blas.2) < 1e-12);
+        assert!(max_difference(&native.3, &blas.3) < 1e-12);
+        assert_eq!(blas.4, SimdMatrix::zeros(67, 83));
+        assert!(max_difference(&native.5.unwrap(), &blas.5.unwrap()) < 1e-12);
+
+        // Same pivots, factors equal up to rounding, and the same errors.
+        let size = 50;
+        let matrix = SimdMatrix::random(size, size, distribution) + SimdMatrix::identity(size) * 4.;
+        let (native, blas) = (matrix.lu().unwrap(), with_blas(|| matrix.lu()).unwrap());
+        assert_eq!(native.permutation(), blas.permutation());
+        assert!(max_difference(&native.l(), &blas.l()) < 1e-12);
+        assert!(max_difference(native.u(), blas.u()) < 1e-12);
+        assert!((native.determinant() / blas.determinant() - 1.).abs() < 1e-10);
+        let pivoting =
+            SimdMatrix::from(vec![vec![0f64, 2., 1.], vec![1., 1., 0.], vec![3., 0., 1.]]).unwrap();
+        let blas = with_blas(|| pivoting.lu()).unwrap();
+        assert_eq!(blas.permutation(), pivoting.lu().unwrap().permutation());
+        assert!((blas.determinant() - -5.).abs() < 1e-12);
+        for singular in [
+            SimdMatrix::from(vec![vec![1., 2.], vec![2., 4.]]).unwrap(),
+            SimdMatrix::from(vec![vec![1., 2.], vec![1., 2. + 2. * f64::EPSILON]]).unwrap(),
+            SimdMatrix::zeros(2, 3),
+            SimdMatrix::identity(0),
+        ] {
+            assert_eq!(
+                with_blas(|| singular.lu()).map(|i| i.determinant()),
+                singular.lu().map(|i| i.determinant())
+            );
+        }
+
+        let factor = SimdMatrix::random(size, size, distribution);
+        let spd = &(&factor * &factor.transpose()) + &SimdMatrix::identity(size);
+        // Only the upper triangle is read.
+        let mut upper_only = spd.clone();
+        for (i, row) in upper_only.matrix.iter_mut().enumerate() {
+            row.as_mut_slice()[..i].fill(f64::NAN);
+        }
+        let native = spd.cholesky().unwrap();
+        let blas = with_blas(|| upper_only.cholesky()).unwrap();
+        assert!(max_difference(&native.l(), &blas.l()) < 1e-12);
+        let rank_one = SimdVector::from_vector(vec![1., 2., -1., 3.]);
+        for failing in [
+            SimdMatrix::from(vec![vec![1., 2.], vec![2., 1.]]).unwrap(),
+            SimdMatrix::from_fn(4, 4, |i, j| {
+                rank_one[i] * rank_one[j] + if i == j && i < 2 { 1. } else { 0. }
+            }),
+            SimdMatrix::zeros(3, 2),
+        ] {
+            assert_eq!(
+                with_blas(|| failing.cholesky()).unwrap_err(),
+                failing.cholesky().unwrap_err()
+            );
+        }
+    }
+
+    #[test]
+    fn check_matrix_trait_f64() {
+        let left_matrix = SimdMatrix::<f64>::from(vec![vec![1., 2.], vec![3., 4.]]).unwrap();
+        let right_matrix = SimdMatrix::<f64>::from(vec![vec![5., 6.], vec![7., 8.]]).unwrap();
+        let vector = SimdVector::<f64>::from_vector(vec![3., 4.]);
+
+        assert_eq!(vector.magnitude(), 5.);
+        assert_eq!(
+            left_matrix.mul(right_matrix).unwrap().to_vector(),
+            vec![vec![19., 22.], vec![43., 50.]]
+        );
+    }
 }
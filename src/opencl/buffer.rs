@@ -0,0 +1,398 @@
+use std::{mem::size_of, ptr};
+
+use opencl3::{
+    kernel::ExecuteKernel,
+    memory::{Buffer, CL_MEM_READ_WRITE},
+    types::{cl_uint, CL_BLOCKING},
+};
+
+use crate::structures::{
+    matrix_simd::{MatrixOpError, SimdMatrix},
+    vector_simd::{SimdVector, VectorOpError},
+};
+
+use super::{
+    kernels::{REDUCTION_GROUP, REDUCTION_GROUPS, TILE},
+    ClBackendError, ClContext, ClScalar,
+};
+
+/// A vector in device memory, created with `SimdVector::to_device`.
+pub struct DeviceVector<'a, T: ClScalar = f32> {
+    context: &'a ClContext,
+    buffer: Buffer<T>,
+    len: usize,
+}
+
+/// A row-major matrix in device memory, created with `SimdMatrix::to_device`.
+pub struct DeviceMatrix<'a, T: ClScalar = f32> {
+    context: &'a ClContext,
+    buffer: Buffer<T>,
+    rows: usize,
+    columns: usize,
+}
+
+impl ClContext {
+    /// Buffers can't be empty, so empty data gets a single unused element.
+    fn allocate<T: ClScalar>(&self, len: usize) -> Result<Buffer<T>, ClBackendError> {
+        Ok(unsafe {
+            Buffer::create(
+                &self.context,
+                CL_MEM_READ_WRITE,
+                len.max(1),
+                ptr::null_mut(),
+            )?
+        })
+    }
+
+    fn upload<T: ClScalar>(&self, data: &[T]) -> Result<Buffer<T>, ClBackendError> {
+        let mut buffer = self.allocate(data.len())?;
+        if !data.is_empty() {
+            unsafe {
+                self.queue
+                    .enqueue_write_buffer(&mut buffer, CL_BLOCKING, 0, data, &[])?
+            };
+        }
+        Ok(buffer)
+    }
+
+    fn download<T: ClScalar>(
+        &self,
+        buffer: &Buffer<T>,
+        len: usize,
+    ) -> Result<Vec<T>, ClBackendError> {
+        let mut data = vec![T::ZERO; len];
+        if len > 0 {
+            unsafe {
+                self.queue
+                    .enqueue_read_buffer(buffer, CL_BLOCKING, 0, &mut data, &[])?
+            };
+        }
+        Ok(data)
+    }
+
+    /// `out = lhs op rhs` for one of the `ELEMENTWISE` kernels.
+    fn elementwise<T: ClScalar>(
+        &self,
+        name: &str,
+        lhs: &Buffer<T>,
+        rhs: &Buffer<T>,
+        len: usize,
+    ) -> Result<Buffer<T>, ClBackendError> {
+        let out = self.allocate(len)?;
+        if len > 0 {
+            let kernel = self.kernel::<T>(name)?;
+            unsafe {
+                ExecuteKernel::new(&kernel)
+                    .set_arg(lhs)
+                    .set_arg(rhs)
+                    .set_arg(&out)
+                    .set_global_work_size(len)
+                    .enqueue_nd_range(&self.queue)?
+            };
+        }
+        Ok(out)
+    }
+
+    /// Sum of `lhs`, or of `lhs * rhs` elementwise. The device leaves one
+    /// partial sum per work group, which are few enough to add up here.
+    fn reduce<T: ClScalar>(
+        &self,
+        lhs: &Buffer<T>,
+        rhs: Option<&Buffer<T>>,
+        len: usize,
+    ) -> Result<T, ClBackendError> {
+        if len == 0 {
+            return Ok(T::ZERO);
+        }
+        let groups = len.div_ceil(REDUCTION_GROUP).min(REDUCTION_GROUPS);
+        let partial = self.allocate::<T>(groups)?;
+        let kernel = self.kernel::<T>("dot")?;
+        let use_rhs = rhs.is_some() as cl_uint;
+        unsafe {
+            ExecuteKernel::new(&kernel)
+                .set_arg(lhs)
+                .set_arg(rhs.unwrap_or(lhs))
+                .set_arg(&use_rhs)
+                .set_arg(&(len as cl_uint))
+                .set_arg(&partial)
+                .set_arg_local_buffer(REDUCTION_GROUP * size_of::<T>())
+                .set_global_work_size(groups * REDUCTION_GROUP)
+                .set_local_work_size(REDUCTION_GROUP)
+                .enqueue_nd_range(&self.queue)?
+        };
+        Ok(self
+            .download(&partial, groups)?
+            .into_iter()
+            .fold(T::ZERO, |acc, i| acc + i))
+    }
+}
+
+impl<T: ClScalar> SimdVector<T> {
+    /// Copies the vector into a new buffer on `context`'s device.
+    pub fn to_device<'a>(
+        &self,
+        context: &'a ClContext,
+    ) -> Result<DeviceVector<'a, T>, ClBackendError> {
+        Ok(DeviceVector {
+            context,
+            buffer: context.upload(self.as_slice())?,
+            len: self.len(),
+        })
+    }
+}
+
+impl<T: ClScalar> SimdMatrix<T> {
+    /// Copies the matrix into a new row-major buffer on `context`'s device.
+    pub fn to_device<'a>(
+        &self,
+        context: &'a ClContext,
+    ) -> Result<DeviceMatrix<'a, T>, ClBackendError> {
+        let data: Vec<T> = self
+            .matrix
+            .iter()
+            .flat_map(|row| row.as_slice().iter().copied())
+            .collect();
+        Ok(DeviceMatrix {
+            context,
+            buffer: context.upload(&data)?,
+            rows: self.height(),
+            columns: self.row_size,
+        })
+    }
+}
+
+impl<'a, T: ClScalar> DeviceVector<'a, T> {
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Copies the vector back into host memory.
+    pub fn to_host(&self) -> Result<SimdVector<T>, ClBackendError> {
+        Ok(SimdVector::from_vector(
+            self.context.download(&self.buffer, self.len)?,
+        ))
+    }
+
+    fn check_same_length(&self, rhs: &DeviceVector<T>) -> Result<(), VectorOpError> {
+        if self.len != rhs.len {
+            return Err(VectorOpError::LengthMismatch {
+                lhs: self.len,
+                rhs: rhs.len,
+            });
+        }
+        Ok(())
+    }
+
+    fn elementwise(
+        &self,
+        name: &str,
+        rhs: &DeviceVector<T>,
+    ) -> Result<DeviceVector<'a, T>, ClBackendError> {
+        self.check_same_length(rhs)?;
+        Ok(DeviceVector {
+            context: self.context,
+            buffer: self
+                .context
+                .elementwise(name, &self.buffer, &rhs.buffer, self.len)?,
+            len: self.len,
+        })
+    }
+
+    pub fn checked_add(
+        &self,
+        rhs: &DeviceVector<T>,
+    ) -> Result<DeviceVector<'a, T>, ClBackendError> {
+        self.elementwise("add", rhs)
+    }
+
+    pub fn checked_sub(
+        &self,
+        rhs: &DeviceVector<T>,
+    ) -> Result<DeviceVector<'a, T>, ClBackendError> {
+        self.elementwise("sub", rhs)
+    }
+
+    pub fn checked_mul(
+        &self,
+        rhs: &DeviceVector<T>,
+    ) -> Result<DeviceVector<'a, T>, ClBackendError> {
+        self.elementwise("mul", rhs)
+    }
+
+    pub fn checked_div(
+        &self,
+        rhs: &DeviceVector<T>,
+    ) -> Result<DeviceVector<'a, T>, ClBackendError> {
+        self.elementwise("div", rhs)
+    }
+
+    pub fn checked_dot(&self, rhs: &DeviceVector<T>) -> Result<T, ClBackendError> {
+        self.check_same_length(rhs)?;
+        self.context
+            .reduce(&self.buffer, Some(&rhs.buffer), self.len)
+    }
+
+    pub fn sum(&self) -> Result<T, ClBackendError> {
+        self.context.reduce(&self.buffer, None, self.len)
+    }
+
+    /// `self += alpha * x`, in place on the device.
+    pub fn axpy(&mut self, alpha: T, x: &DeviceVector<T>) -> Result<(), ClBackendError> {
+        self.check_same_length(x)?;
+        if self.len > 0 {
+            let kernel = self.context.kernel::<T>("axpy")?;
+            unsafe {
+                ExecuteKernel::new(&kernel)
+                    .set_arg(&alpha)
+                    .set_arg(&x.buffer)
+                    .set_arg(&self.buffer)
+                    .set_global_work_size(self.len)
+                    .enqueue_nd_range(&self.context.queue)?
+            };
+        }
+        Ok(())
+    }
+
+    /// Multiplies every element by `alpha`, in place on the device.
+    pub fn scale(self, alpha: T) -> Result<DeviceVector<'a, T>, ClBackendError> {
+        if self.len > 0 {
+            let kernel = self.context.kernel::<T>("scale")?;
+            unsafe {
+                ExecuteKernel::new(&kernel)
+                    .set_arg(&alpha)
+                    .set_arg(&self.buffer)
+                    .set_global_work_size(self.len)
+                    .enqueue_nd_range(&self.context.queue)?
+            };
+        }
+        Ok(self)
+    }
+}
+
+impl<'a, T: ClScalar> DeviceMatrix<'a, T> {
+    pub fn dimensions(&self) -> (usize, usize) {
+        (self.rows, self.columns)
+    }
+
+    /// Copies the matrix back into host memory.
+    pub fn to_host(&self) -> Result<SimdMatrix<T>, ClBackendError> {
+        let data = self
+            .context
+            .download(&self.buffer, self.rows * self.columns)?;
+        Ok(SimdMatrix::from_row_slice(self.rows, self.columns, &data).unwrap())
+    }
+
+    fn check_same_dimensions(&self, rhs: &DeviceMatrix<T>) -> Result<(), MatrixOpError> {
+        if self.dimensions() != rhs.dimensions() {
+            return Err(MatrixOpError::DimensionMismatch {
+                lhs: self.dimensions(),
+                rhs: rhs.dimensions(),
+            });
+        }
+        Ok(())
+    }
+
+    fn elementwise(
+        &self,
+        name: &str,
+        rhs: &DeviceMatrix<T>,
+    ) -> Result<DeviceMatrix<'a, T>, ClBackendError> {
+        self.check_same_dimensions(rhs)?;
+        Ok(DeviceMatrix {
+            context: self.context,
+            buffer: self.context.elementwise(
+                name,
+                &self.buffer,
+                &rhs.buffer,
+                self.rows * self.columns,
+            )?,
+            rows: self.rows,
+            columns: self.columns,
+        })
+    }
+
+    pub fn try_add(&self, rhs: &DeviceMatrix<T>) -> Result<DeviceMatrix<'a, T>, ClBackendError> {
+        self.elementwise("add", rhs)
+    }
+
+    pub fn try_sub(&self, rhs: &DeviceMatrix<T>) -> Result<DeviceMatrix<'a, T>, ClBackendError> {
+        self.elementwise("sub", rhs)
+    }
+
+    pub fn try_hadamard(
+        &self,
+        rhs: &DeviceMatrix<T>,
+    ) -> Result<DeviceMatrix<'a, T>, ClBackendError> {
+        self.elementwise("mul", rhs)
+    }
+
+    /// Matrix product on the device, computed in local-memory tiles.
+    pub fn try_mul(&self, rhs: &DeviceMatrix<T>) -> Result<DeviceMatrix<'a, T>, ClBackendError> {
+        if self.columns != rhs.rows {
+            return Err(MatrixOpError::DimensionMismatch {
+                lhs: self.dimensions(),
+                rhs: rhs.dimensions(),
+            }
+            .into());
+        }
+        let (m, n, k) = (self.rows, rhs.columns, self.columns);
+        let out = self.context.allocate::<T>(m * n)?;
+        if m * n > 0 {
+            let kernel = self.context.kernel::<T>("gemm")?;
+            unsafe {
+                ExecuteKernel::new(&kernel)
+                    .set_arg(&(m as cl_uint))
+                    .set_arg(&(n as cl_uint))
+                    .set_arg(&(k as cl_uint))
+                    .set_arg(&self.buffer)
+                    .set_arg(&rhs.buffer)
+                    .set_arg(&out)
+                    .set_global_work_sizes(&[n.next_multiple_of(TILE), m.next_multiple_of(TILE)])
+                    .set_local_work_sizes(&[TILE, TILE])
+                    .enqueue_nd_range(&self.context.queue)?
+            };
+        }
+        Ok(DeviceMatrix {
+            context: self.context,
+            buffer: out,
+            rows: m,
+            columns: n,
+        })
+    }
+
+    /// Matrix-vector product on the device, one work item per row.
+    pub fn try_mul_vec(
+        &self,
+        rhs: &DeviceVector<T>,
+    ) -> Result<DeviceVector<'a, T>, ClBackendError> {
+        if self.columns != rhs.len {
+            return Err(MatrixOpError::DimensionMismatch {
+                lhs: self.dimensions(),
+                rhs: (rhs.len, 1),
+            }
+            .into());
+        }
+        let out = self.context.allocate::<T>(self.rows)?;
+        if self.rows > 0 {
+            let kernel = self.context.kernel::<T>("gemv")?;
+            unsafe {
+                ExecuteKernel::new(&kernel)
+                    .set_arg(&self.buffer)
+                    .set_arg(&rhs.buffer)
+                    .set_arg(&out)
+                    .set_arg(&(self.columns as cl_uint))
+                    .set_global_work_size(self.rows)
+                    .enqueue_nd_range(&self.context.queue)?
+            };
+        }
+        Ok(DeviceVector {
+            context: self.context,
+            buffer: out,
+            len: self.rows,
+        })
+    }
+}
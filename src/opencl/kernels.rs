@@ -0,0 +1,104 @@
+/// Side length of the square tiles `gemm` stages in local memory, and so of
+/// its work groups.
+pub(super) const TILE: usize = 16;
+
+/// Work-group size of the reductions. Must be a power of two.
+pub(super) const REDUCTION_GROUP: usize = 64;
+
+/// Upper bound on the number of work groups a reduction launches; each
+/// leaves one partial sum that is added up on the host.
+pub(super) const REDUCTION_GROUPS: usize = 64;
+
+/// OpenCL C source of every kernel, compiled once per element type with
+/// `REAL` defined as that type's OpenCL name.
+pub(super) const SOURCE: &str = r#"
+#ifdef USE_DOUBLE
+#pragma OPENCL EXTENSION cl_khr_fp64 : enable
+#endif
+
+#define TILE 16
+
+#define ELEMENTWISE(name, op)                                              \
+    __kernel void name(__global const REAL *lhs, __global const REAL *rhs, \
+                       __global REAL *out) {                               \
+        size_t i = get_global_id(0);                                       \
+        out[i] = lhs[i] op rhs[i];                                         \
+    }
+
+ELEMENTWISE(add, +)
+ELEMENTWISE(sub, -)
+ELEMENTWISE(mul, *)
+ELEMENTWISE(div, /)
+
+__kernel void axpy(const REAL alpha, __global const REAL *x, __global REAL *y) {
+    size_t i = get_global_id(0);
+    y[i] += alpha * x[i];
+}
+
+__kernel void scale(const REAL alpha, __global REAL *x) {
+    size_t i = get_global_id(0);
+    x[i] *= alpha;
+}
+
+/* Each work group folds a grid-strided slice into local memory, reduces it
+   as a tree and writes one partial sum. With `use_rhs` zero `rhs` is ignored
+   and this is a plain sum rather than a dot product. */
+__kernel void dot(__global const REAL *lhs, __global const REAL *rhs,
+                  const uint use_rhs, const uint n,
+                  __global REAL *partial, __local REAL *scratch) {
+    size_t local_id = get_local_id(0);
+    REAL sum = 0;
+    for (size_t i = get_global_id(0); i < n; i += get_global_size(0)) {
+        sum += use_rhs ? lhs[i] * rhs[i] : lhs[i];
+    }
+    scratch[local_id] = sum;
+    barrier(CLK_LOCAL_MEM_FENCE);
+    for (size_t stride = get_local_size(0) / 2; stride > 0; stride /= 2) {
+        if (local_id < stride) {
+            scratch[local_id] += scratch[local_id + stride];
+        }
+        barrier(CLK_LOCAL_MEM_FENCE);
+    }
+    if (local_id == 0) {
+        partial[get_group_id(0)] = scratch[0];
+    }
+}
+
+/* One work item per row of the row-major `rows x columns` matrix. */
+__kernel void gemv(__global const REAL *a, __global const REAL *x,
+                   __global REAL *y, const uint columns) {
+    size_t row = get_global_id(0);
+    REAL sum = 0;
+    for (uint j = 0; j < columns; j++) {
+        sum += a[row * columns + j] * x[j];
+    }
+    y[row] = sum;
+}
+
+/* `c = a * b` for row-major `m x k` and `k x n` matrices, one element of `c`
+   per work item. The global size is rounded up to whole tiles; out of range
+   items load zeros and skip the store. */
+__kernel void gemm(const uint m, const uint n, const uint k,
+                   __global const REAL *a, __global const REAL *b,
+                   __global REAL *c) {
+    __local REAL a_tile[TILE][TILE];
+    __local REAL b_tile[TILE][TILE];
+    size_t row = get_global_id(1), column = get_global_id(0);
+    size_t local_row = get_local_id(1), local_column = get_local_id(0);
+    REAL sum = 0;
+    for (uint start = 0; start < k; start += TILE) {
+        a_tile[local_row][local_column] = row < m && start + local_column < k
+            ? a[row * k + start + local_column] : 0;
+        b_tile[local_row][local_column] = start + local_row < k && column < n
+            ? b[(start + local_row) * n + column] : 0;
+        barrier(CLK_LOCAL_MEM_FENCE);
+        for (uint i = 0; i < TILE; i++) {
+            sum += a_tile[local_row][i] * b_tile[i][local_column];
+        }
+        barrier(CLK_LOCAL_MEM_FENCE);
+    }
+    if (row < m && column < n) {
+        c[row * n + column] = sum;
+    }
+}
+"#;
@@ -1 +1,182 @@
+//! OpenCL compute backend.
+//!
+//! A [`ClContext`] owns a device, a context, an in-order command queue and the
+//! kernels compiled for that device. `SimdVector::to_device` and
+//! `SimdMatrix::to_device` copy data into [`DeviceVector`] and
+//! [`DeviceMatrix`] buffers, whose operations run entirely on the device until
+//! `to_host` copies the result back. Transfers are always explicit, so chains
+//! of operations do not round-trip through host memory.
+//!
+//! Any conformant OpenCL 2.0 implementation works, including PoCL on
+//! machines without a GPU. `f64` needs the `cl_khr_fp64` extension.
 
+mod buffer;
+mod kernels;
+
+use opencl3::{
+    command_queue::CommandQueue,
+    context::Context,
+    device::{Device, CL_DEVICE_TYPE_ALL, CL_DEVICE_TYPE_GPU},
+    error_codes::ClError,
+    kernel::Kernel,
+    platform::get_platforms,
+    program::Program,
+};
+use thiserror::Error;
+
+use crate::structures::{
+    matrix_simd::MatrixOpError, scalar::SimdScalar, vector_simd::VectorOpError,
+};
+
+pub use buffer::{DeviceMatrix, DeviceVector};
+
+/// Element types with an OpenCL C counterpart.
+pub trait ClScalar: SimdScalar {
+    /// The OpenCL C name of the type, which the kernels are compiled for.
+    const NAME: &'static str;
+    /// Extra build options, e.g. to enable an extension the type needs.
+    const BUILD_OPTIONS: &'static str;
+    /// Device extension the type needs, if any.
+    const EXTENSION: Option<&'static str>;
+}
+
+impl ClScalar for f32 {
+    const NAME: &'static str = "float";
+    const BUILD_OPTIONS: &'static str = "";
+    const EXTENSION: Option<&'static str> = None;
+}
+
+impl ClScalar for f64 {
+    const NAME: &'static str = "double";
+    const BUILD_OPTIONS: &'static str = "-D USE_DOUBLE";
+    const EXTENSION: Option<&'static str> = Some("cl_khr_fp64");
+}
+
+#[derive(Debug, Error)]
+pub enum ClBackendError {
+    #[error("No OpenCL device is available")]
+    NoDevice,
+    #[error("OpenCL call failed: {0}")]
+    Cl(ClError),
+    #[error("OpenCL kernels failed to build for {element}: {log}")]
+    Build { element: &'static str, log: String },
+    #[error("The OpenCL device does not support {0} arithmetic")]
+    UnsupportedType(&'static str),
+    #[error(transparent)]
+    LengthMismatch(#[from] VectorOpError),
+    #[error(transparent)]
+    DimensionMismatch(#[from] MatrixOpError),
+}
+
+impl From<ClError> for ClBackendError {
+    fn from(error: ClError) -> Self {
+        ClBackendError::Cl(error)
+    }
+}
+
+/// A device found by [`ClContext::devices`].
+#[derive(Debug)]
+pub struct DeviceInfo {
+    pub platform: String,
+    pub name: String,
+    pub device: Device,
+}
+
+/// A device together with the context, queue and compiled kernels needed to
+/// run on it.
+pub struct ClContext {
+    device: Device,
+    context: Context,
+    queue: CommandQueue,
+    /// Programs compiled so far, keyed by `ClScalar::NAME`.
+    programs: Vec<(&'static str, Program)>,
+}
+
+impl ClContext {
+    /// Every device on every platform.
+    pub fn devices() -> Result<Vec<DeviceInfo>, ClBackendError> {
+        let mut devices = vec![];
+        for platform in get_platforms()? {
+            let platform_name = platform.name()?;
+            for id in platform.get_devices(CL_DEVICE_TYPE_ALL)? {
+                let device = Device::new(id);
+                devices.push(DeviceInfo {
+                    platform: platform_name.clone(),
+                    name: device.name()?,
+                    device,
+                });
+            }
+        }
+        Ok(devices)
+    }
+
+    /// A context on the first GPU, or on the first device of any kind if
+    /// there is no GPU, e.g. a CPU device provided by PoCL.
+    pub fn new() -> Result<ClContext, ClBackendError> {
+        let mut fallback = None;
+        for platform in get_platforms()? {
+            if let Some(&id) = platform.get_devices(CL_DEVICE_TYPE_GPU)?.first() {
+                return Self::from_device(Device::new(id));
+            }
+            if fallback.is_none() {
+                fallback = platform.get_devices(CL_DEVICE_TYPE_ALL)?.first().copied();
+            }
+        }
+        Self::from_device(Device::new(fallback.ok_or(ClBackendError::NoDevice)?))
+    }
+
+    /// A context on `device`, with the `f32` kernels compiled and the `f64`
+    /// ones too if the device supports them.
+    pub fn from_device(device: Device) -> Result<ClContext, ClBackendError> {
+        let context = Context::from_device(&device)?;
+        let queue = CommandQueue::create_default_with_properties(&context, 0, 0)?;
+        let mut out = ClContext {
+            device,
+            context,
+            queue,
+            programs: vec![],
+        };
+        out.compile::<f32>()?;
+        if out.supports::<f64>()? {
+            out.compile::<f64>()?;
+        }
+        Ok(out)
+    }
+
+    pub fn device(&self) -> &Device {
+        &self.device
+    }
+
+    /// Whether the device has the extensions `T` needs.
+    pub fn supports<T: ClScalar>(&self) -> Result<bool, ClBackendError> {
+        Ok(match T::EXTENSION {
+            Some(extension) => self
+                .device
+                .extensions()?
+                .split_whitespace()
+                .any(|i| i == extension),
+            None => true,
+        })
+    }
+
+    fn compile<T: ClScalar>(&mut self) -> Result<(), ClBackendError> {
+        let options = format!("-D REAL={} {}", T::NAME, T::BUILD_OPTIONS);
+        let program =
+            Program::create_and_build_from_source(&self.context, kernels::SOURCE, &options)
+                .map_err(|log| ClBackendError::Build {
+                    element: T::NAME,
+                    log,
+                })?;
+        self.programs.push((T::NAME, program));
+        Ok(())
+    }
+
+    fn kernel<T: ClScalar>(&self, name: &str) -> Result<Kernel, ClBackendError> {
+        let (_, program) = self
+            .programs
+            .iter()
+            .find(|(element, _)| *element == T::NAME)
+            .ok_or(ClBackendError::UnsupportedType(T::NAME))?;
+        Ok(Kernel::create(program, name)?)
+    }
+}
@@ -0,0 +1,177 @@
+//! The SIMD chunk type the vectors and matrices are stored in.
+//!
+//! With the `nightly-simd` feature these are the `std::simd` types. Without
+//! it, `Simd` is a plain array wrapper with the same size and alignment and
+//! the subset of the `std::simd` API this crate uses, and the lane kernels in
+//! [`SimdScalar`](crate::structures::scalar::SimdScalar) become lane-by-lane
+//! loops that the compiler is free to autovectorize. Code written against
+//! `cliff::simd` builds the same way in both configurations.
+
+#[cfg(feature = "nightly-simd")]
+pub use std::simd::{Simd, SimdElement};
+
+#[cfg(not(feature = "nightly-simd"))]
+pub use portable::{Simd, SimdElement};
+
+/// Marker for lane counts. `std::simd` no longer has one, so the crate keeps
+/// its own and the `LaneCount<N>: SupportedLaneCount` bounds mean the same
+/// in both configurations.
+pub struct LaneCount<const N: usize>;
+
+/// Implemented for the lane counts the crate supports: powers of two up to
+/// 64.
+pub trait SupportedLaneCount {
+    /// Zero sized type with the alignment of `N` lanes of `T`.
+    #[cfg(not(feature = "nightly-simd"))]
+    #[doc(hidden)]
+    type Align<T: SimdElement>: portable::Alignment;
+}
+
+macro_rules! impl_supported_lane_count {
+    ($($n:literal => $align:ident),*) => {
+        $(impl SupportedLaneCount for LaneCount<$n> {
+            #[cfg(not(feature = "nightly-simd"))]
+            type Align<T: SimdElement> = T::$align;
+        })*
+    };
+}
+
+impl_supported_lane_count!(
+    1 => Align1,
+    2 => Align2,
+    4 => Align4,
+    8 => Align8,
+    16 => Align16,
+    32 => Align32,
+    64 => Align64
+);
+
+#[cfg(not(feature = "nightly-simd"))]
+mod portable {
+    use std::{fmt, hash::Hash};
+
+    use super::{LaneCount, SupportedLaneCount};
+
+    /// Bounds of the zero sized alignment markers, so the traits derived for
+    /// `Simd` hold.
+    pub trait Alignment: Copy + Default + Eq + Ord + Hash + fmt::Debug + Send + Sync {}
+
+    macro_rules! alignments {
+        ($($name:ident = $bytes:literal),*) => {
+            $(
+                #[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+                #[repr(align($bytes))]
+                pub struct $name;
+
+                impl Alignment for $name {}
+            )*
+        };
+    }
+
+    alignments!(A1 = 1, A2 = 2, A4 = 4, A8 = 8, A16 = 16, A32 = 32, A64 = 64);
+
+    /// Element types that can be stored in a [`Simd`].
+    ///
+    /// The associated types give the alignment of 1, 2, .., 64 lanes: the
+    /// size of the lanes, as in `std::simd`, capped at a 64 byte cache line.
+    pub trait SimdElement: Copy {
+        #[doc(hidden)]
+        type Align1: Alignment;
+        #[doc(hidden)]
+        type Align2: Alignment;
+        #[doc(hidden)]
+        type Align4: Alignment;
+        #[doc(hidden)]
+        type Align8: Alignment;
+        #[doc(hidden)]
+        type Align16: Alignment;
+        #[doc(hidden)]
+        type Align32: Alignment;
+        #[doc(hidden)]
+        type Align64: Alignment;
+    }
+
+    macro_rules! impl_simd_element {
+        ($([$($t:ty),*] => $a1:ident, $a2:ident, $a4:ident, $a8:ident, $a16:ident, $a32:ident, $a64:ident);*) => {
+            $($(impl SimdElement for $t {
+                type Align1 = $a1;
+                type Align2 = $a2;
+                type Align4 = $a4;
+                type Align8 = $a8;
+                type Align16 = $a16;
+                type Align32 = $a32;
+                type Align64 = $a64;
+            })*)*
+        };
+    }
+
+    impl_simd_element!(
+        [i8, u8] => A1, A2, A4, A8, A16, A32, A64;
+        [i16, u16] => A2, A4, A8, A16, A32, A64, A64;
+        [f32, i32, u32] => A4, A8, A16, A32, A64, A64, A64;
+        [f64, i64, u64] => A8, A16, A32, A64, A64, A64, A64
+    );
+
+    /// `N` lanes of `T`, the same size as `[T; N]` but aligned to that size
+    /// like `std::simd` vectors, so a chunk never straddles a cache line.
+    #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Hash)]
+    #[repr(C)]
+    pub struct Simd<T: SimdElement, const N: usize>
+    where
+        LaneCount<N>: SupportedLaneCount,
+    {
+        lanes: [T; N],
+        align: [<LaneCount<N> as SupportedLaneCount>::Align<T>; 0],
+    }
+
+    impl<T: SimdElement, const N: usize> Simd<T, N>
+    where
+        LaneCount<N>: SupportedLaneCount,
+    {
+        pub fn splat(value: T) -> Self {
+            Self::from_array([value; N])
+        }
+
+        pub const fn from_array(lanes: [T; N]) -> Self {
+            Simd { lanes, align: [] }
+        }
+
+        /// Reads the first `N` elements of `slice`. Panics if it is shorter.
+        pub fn from_slice(slice: &[T]) -> Self {
+            Self::from_array(slice[..N].try_into().unwrap())
+        }
+
+        pub const fn to_array(self) -> [T; N] {
+            self.lanes
+        }
+
+        pub const fn as_array(&self) -> &[T; N] {
+            &self.lanes
+        }
+
+        pub fn as_mut_array(&mut self) -> &mut [T; N] {
+            &mut self.lanes
+        }
+
+        /// `f` applied lane by lane.
+        #[inline]
+        pub(crate) fn map_lanes(self, f: impl Fn(T) -> T) -> Self {
+            Self::from_array(self.lanes.map(f))
+        }
+
+        /// `f` applied to each pair of matching lanes.
+        #[inline]
+        pub(crate) fn zip_lanes(self, rhs: Self, f: impl Fn(T, T) -> T) -> Self {
+            Self::from_array(std::array::from_fn(|i| f(self.lanes[i], rhs.lanes[i])))
+        }
+    }
+
+    impl<T: SimdElement + fmt::Debug, const N: usize> fmt::Debug for Simd<T, N>
+    where
+        LaneCount<N>: SupportedLaneCount,
+    {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_tuple("Simd").field(&self.lanes).finish()
+        }
+    }
+}
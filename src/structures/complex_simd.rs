@@ -0,0 +1,423 @@
+use std::ops::{Add, Mul, Neg, Sub};
+
+use num::{Complex, Float};
+
+use super::{
+    matrix_simd::{MatrixCreationError, MatrixOpError, SimdLineIter, SimdMatrix},
+    scalar::SimdScalar,
+    vector_simd::{SimdVector, VectorOpError},
+};
+
+/// A complex vector stored as two [`SimdVector`]s, one for the real parts and
+/// one for the imaginary parts.
+///
+/// Keeping the parts in separate lanes rather than interleaving them means
+/// every complex operation is a handful of ordinary real SIMD operations, e.g.
+/// a product is `(ac - bd) + (ad + bc)i` chunk by chunk. As with `SimdVector`,
+/// the operators panic on mismatched lengths and the `checked_` methods
+/// return a [`VectorOpError`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComplexSimdVector<T: SimdScalar = f32> {
+    re: SimdVector<T>,
+    im: SimdVector<T>,
+}
+
+/// A complex matrix stored as a real and an imaginary [`SimdMatrix`], so
+/// products, transposes and row access reuse the real kernels.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComplexSimdMatrix<T: SimdScalar = f32> {
+    re: SimdMatrix<T>,
+    im: SimdMatrix<T>,
+}
+
+/// Row or column iterator over a [`ComplexSimdMatrix`].
+pub struct ComplexLineIter<'a, T: SimdScalar> {
+    re: SimdLineIter<'a, T>,
+    im: SimdLineIter<'a, T>,
+}
+
+impl<T: SimdScalar> ComplexSimdVector<T> {
+    pub fn from_parts(
+        re: SimdVector<T>,
+        im: SimdVector<T>,
+    ) -> Result<ComplexSimdVector<T>, VectorOpError> {
+        if re.len() == im.len() {
+            Ok(ComplexSimdVector { re, im })
+        } else {
+            Err(VectorOpError::LengthMismatch {
+                lhs: re.len(),
+                rhs: im.len(),
+            })
+        }
+    }
+
+    pub fn from_vector(vec: Vec<Complex<T>>) -> ComplexSimdVector<T> {
+        ComplexSimdVector {
+            re: SimdVector::from_vector(vec.iter().map(|i| i.re).collect()),
+            im: SimdVector::from_vector(vec.iter().map(|i| i.im).collect()),
+        }
+    }
+
+    /// A complex vector with the given real parts and zero imaginary parts.
+    pub fn from_real(re: SimdVector<T>) -> ComplexSimdVector<T> {
+        let im = SimdVector::zeros(re.len());
+        ComplexSimdVector { re, im }
+    }
+
+    pub fn zeros(len: usize) -> ComplexSimdVector<T> {
+        Self::from_real(SimdVector::zeros(len))
+    }
+
+    pub fn to_vector(&self) -> Vec<Complex<T>> {
+        self.re
+            .iter()
+            .zip(self.im.iter())
+            .map(|(re, im)| Complex::new(re, im))
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.re.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.re.is_empty()
+    }
+
+    pub fn get(&self, index: usize) -> Option<Complex<T>> {
+        Some(Complex::new(self.re.get(index)?, self.im.get(index)?))
+    }
+
+    pub fn re(&self) -> &SimdVector<T> {
+        &self.re
+    }
+
+    pub fn im(&self) -> &SimdVector<T> {
+        &self.im
+    }
+
+    pub fn into_parts(self) -> (SimdVector<T>, SimdVector<T>) {
+        (self.re, self.im)
+    }
+
+    pub fn sum(&self) -> Complex<T> {
+        Complex::new(self.re.sum(), self.im.sum())
+    }
+
+    pub fn conjugate(mut self) -> ComplexSimdVector<T> {
+        self.im.negate();
+        self
+    }
+
+    pub fn scale(&self, rhs: Complex<T>) -> ComplexSimdVector<T> {
+        let mut re = &self.re * rhs.re;
+        re.axpy(T::ZERO - rhs.im, &self.im);
+        let mut im = &self.im * rhs.re;
+        im.axpy(rhs.im, &self.re);
+        ComplexSimdVector { re, im }
+    }
+
+    pub fn checked_add(
+        &self,
+        rhs: &ComplexSimdVector<T>,
+    ) -> Result<ComplexSimdVector<T>, VectorOpError> {
+        Ok(ComplexSimdVector {
+            re: self.re.checked_add(&rhs.re)?,
+            im: self.im.checked_add(&rhs.im)?,
+        })
+    }
+
+    pub fn checked_sub(
+        &self,
+        rhs: &ComplexSimdVector<T>,
+    ) -> Result<ComplexSimdVector<T>, VectorOpError> {
+        Ok(ComplexSimdVector {
+            re: self.re.checked_sub(&rhs.re)?,
+            im: self.im.checked_sub(&rhs.im)?,
+        })
+    }
+
+    /// Elementwise complex product, `(ac - bd) + (ad + bc)i`, from four real
+    /// elementwise products so that it runs on the current backend.
+    pub fn checked_mul(
+        &self,
+        rhs: &ComplexSimdVector<T>,
+    ) -> Result<ComplexSimdVector<T>, VectorOpError> {
+        let mut re = self.re.checked_mul(&rhs.re)?;
+        re -= &self.im.checked_mul(&rhs.im)?;
+        let mut im = self.re.checked_mul(&rhs.im)?;
+        im += &self.im.checked_mul(&rhs.re)?;
+        Ok(ComplexSimdVector { re, im })
+    }
+
+    /// Hermitian inner product `Σ conj(self_i) * rhs_i`, from four real SIMD
+    /// dot products.
+    pub fn checked_hermitian_dot(
+        &self,
+        rhs: &ComplexSimdVector<T>,
+    ) -> Result<Complex<T>, VectorOpError> {
+        let re = self.re.checked_dot(&rhs.re)? + self.im.checked_dot(&rhs.im)?;
+        let im = self.re.checked_dot(&rhs.im)? - self.im.checked_dot(&rhs.re)?;
+        Ok(Complex::new(re, im))
+    }
+}
+
+impl<T: SimdScalar + Float> ComplexSimdVector<T> {
+    /// Euclidean norm, `sqrt(Σ |z_i|²)`.
+    pub fn magnitude(&self) -> T {
+        (self.re.checked_dot(&self.re).unwrap() + self.im.checked_dot(&self.im).unwrap()).sqrt()
+    }
+}
+
+impl<T: SimdScalar> ComplexSimdMatrix<T> {
+    pub fn from(input: Vec<Vec<Complex<T>>>) -> Result<ComplexSimdMatrix<T>, MatrixCreationError> {
+        let part = |f: fn(&Complex<T>) -> T| {
+            SimdMatrix::from(
+                input
+                    .iter()
+                    .map(|row| row.iter().map(f).collect())
+                    .collect(),
+            )
+        };
+        Ok(ComplexSimdMatrix {
+            re: part(|i| i.re)?,
+            im: part(|i| i.im)?,
+        })
+    }
+
+    pub fn from_parts(
+        re: SimdMatrix<T>,
+        im: SimdMatrix<T>,
+    ) -> Result<ComplexSimdMatrix<T>, MatrixOpError> {
+        if re.dimensions() == im.dimensions() {
+            Ok(ComplexSimdMatrix { re, im })
+        } else {
+            Err(MatrixOpError::DimensionMismatch {
+                lhs: re.dimensions(),
+                rhs: im.dimensions(),
+            })
+        }
+    }
+
+    /// A complex matrix with the given real parts and zero imaginary parts.
+    pub fn from_real(re: SimdMatrix<T>) -> ComplexSimdMatrix<T> {
+        let (rows, columns) = re.dimensions();
+        ComplexSimdMatrix {
+            re,
+            im: SimdMatrix::zeros(rows, columns),
+        }
+    }
+
+    /// Builds a matrix from its rows, which must all have the same length.
+    pub fn from_rows(
+        rows: Vec<ComplexSimdVector<T>>,
+    ) -> Result<ComplexSimdMatrix<T>, MatrixCreationError> {
+        let (re, im) = rows.into_iter().map(ComplexSimdVector::into_parts).unzip();
+        Ok(ComplexSimdMatrix {
+            re: SimdMatrix::from_simd(re)?,
+            im: SimdMatrix::from_simd(im)?,
+        })
+    }
+
+    pub fn zeros(rows: usize, columns: usize) -> ComplexSimdMatrix<T> {
+        Self::from_real(SimdMatrix::zeros(rows, columns))
+    }
+
+    pub fn identity(size: usize) -> ComplexSimdMatrix<T> {
+        Self::from_real(SimdMatrix::identity(size))
+    }
+
+    pub fn re(&self) -> &SimdMatrix<T> {
+        &self.re
+    }
+
+    pub fn im(&self) -> &SimdMatrix<T> {
+        &self.im
+    }
+
+    pub fn dimensions(&self) -> (usize, usize) {
+        self.re.dimensions()
+    }
+
+    pub fn row(&self, row: usize) -> Option<ComplexSimdVector<T>> {
+        Some(ComplexSimdVector {
+            re: self.re.row(row)?,
+            im: self.im.row(row)?,
+        })
+    }
+
+    pub fn column(&self, column: usize) -> Option<ComplexSimdVector<T>> {
+        Some(ComplexSimdVector {
+            re: self.re.column(column)?,
+            im: self.im.column(column)?,
+        })
+    }
+
+    pub fn iter_row(&self) -> ComplexLineIter<'_, T> {
+        ComplexLineIter {
+            re: self.re.iter_row(),
+            im: self.im.iter_row(),
+        }
+    }
+
+    pub fn iter_column(&self) -> ComplexLineIter<'_, T> {
+        ComplexLineIter {
+            re: self.re.iter_column(),
+            im: self.im.iter_column(),
+        }
+    }
+
+    pub fn to_vector(&self) -> Vec<Vec<Complex<T>>> {
+        (0..self.re.height())
+            .map(|i| self.row(i).unwrap().to_vector())
+            .collect()
+    }
+
+    pub fn transpose(&self) -> ComplexSimdMatrix<T> {
+        ComplexSimdMatrix {
+            re: self.re.transpose(),
+            im: self.im.transpose(),
+        }
+    }
+
+    pub fn conjugate(mut self) -> ComplexSimdMatrix<T> {
+        self.im = -self.im;
+        self
+    }
+
+    /// The Hermitian adjoint `Aᴴ`.
+    pub fn conjugate_transpose(&self) -> ComplexSimdMatrix<T> {
+        ComplexSimdMatrix {
+            re: self.re.transpose(),
+            im: -self.im.transpose(),
+        }
+    }
+
+    pub fn try_add(
+        &self,
+        rhs: &ComplexSimdMatrix<T>,
+    ) -> Result<ComplexSimdMatrix<T>, MatrixOpError> {
+        Ok(ComplexSimdMatrix {
+            re: self.re.try_add(&rhs.re)?,
+            im: self.im.try_add(&rhs.im)?,
+        })
+    }
+
+    pub fn try_sub(
+        &self,
+        rhs: &ComplexSimdMatrix<T>,
+    ) -> Result<ComplexSimdMatrix<T>, MatrixOpError> {
+        Ok(ComplexSimdMatrix {
+            re: self.re.try_sub(&rhs.re)?,
+            im: self.im.try_sub(&rhs.im)?,
+        })
+    }
+
+    /// Matrix product `(A + Bi)(C + Di) = (AC - BD) + (AD + BC)i`, as four real
+    /// `gemm` calls accumulating straight into the two output parts.
+    pub fn try_mul(
+        &self,
+        rhs: &ComplexSimdMatrix<T>,
+    ) -> Result<ComplexSimdMatrix<T>, MatrixOpError> {
+        let mut re = self.re.try_mul(&rhs.re)?;
+        re.gemm(T::ZERO - T::ONE, &self.im, &rhs.im, T::ONE)?;
+        let mut im = self.re.try_mul(&rhs.im)?;
+        im.gemm(T::ONE, &self.im, &rhs.re, T::ONE)?;
+        Ok(ComplexSimdMatrix { re, im })
+    }
+
+    /// Matrix-vector product `(A + Bi)(x + yi) = (Ax - By) + (Ay + Bx)i`.
+    pub fn try_mul_vec(
+        &self,
+        rhs: &ComplexSimdVector<T>,
+    ) -> Result<ComplexSimdVector<T>, MatrixOpError> {
+        let re = self.re.try_mul_vec(&rhs.re)? - self.im.try_mul_vec(&rhs.im)?;
+        let im = self.re.try_mul_vec(&rhs.im)? + self.im.try_mul_vec(&rhs.re)?;
+        Ok(ComplexSimdVector { re, im })
+    }
+}
+
+impl<'a, T: SimdScalar> Iterator for ComplexLineIter<'a, T> {
+    type Item = ComplexSimdVector<T>;
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(ComplexSimdVector {
+            re: self.re.next()?,
+            im: self.im.next()?,
+        })
+    }
+}
+
+macro_rules! impl_vector_op {
+    ($op:ident, $fn:ident, $checked:ident) => {
+        impl<T: SimdScalar> $op<&ComplexSimdVector<T>> for &ComplexSimdVector<T> {
+            type Output = ComplexSimdVector<T>;
+
+            fn $fn(self, rhs: &ComplexSimdVector<T>) -> Self::Output {
+                self.$checked(rhs).unwrap_or_else(|e| panic!("{e}"))
+            }
+        }
+
+        impl<T: SimdScalar> $op for ComplexSimdVector<T> {
+            type Output = ComplexSimdVector<T>;
+
+            fn $fn(self, rhs: ComplexSimdVector<T>) -> Self::Output {
+                $op::$fn(&self, &rhs)
+            }
+        }
+    };
+}
+
+impl_vector_op!(Add, add, checked_add);
+impl_vector_op!(Sub, sub, checked_sub);
+impl_vector_op!(Mul, mul, checked_mul);
+
+impl<T: SimdScalar> Mul<Complex<T>> for &ComplexSimdVector<T> {
+    type Output = ComplexSimdVector<T>;
+
+    fn mul(self, rhs: Complex<T>) -> Self::Output {
+        self.scale(rhs)
+    }
+}
+
+impl<T: SimdScalar> Neg for ComplexSimdVector<T> {
+    type Output = ComplexSimdVector<T>;
+
+    fn neg(self) -> Self::Output {
+        ComplexSimdVector {
+            re: -self.re,
+            im: -self.im,
+        }
+    }
+}
+
+macro_rules! impl_matrix_op {
+    ($op:ident, $fn:ident, $checked:ident) => {
+        impl<T: SimdScalar> $op<&ComplexSimdMatrix<T>> for &ComplexSimdMatrix<T> {
+            type Output = ComplexSimdMatrix<T>;
+
+            fn $fn(self, rhs: &ComplexSimdMatrix<T>) -> Self::Output {
+                self.$checked(rhs).unwrap_or_else(|e| panic!("{e}"))
+            }
+        }
+
+        impl<T: SimdScalar> $op for ComplexSimdMatrix<T> {
+            type Output = ComplexSimdMatrix<T>;
+
+            fn $fn(self, rhs: ComplexSimdMatrix<T>) -> Self::Output {
+                $op::$fn(&self, &rhs)
+            }
+        }
+    };
+}
+
+impl_matrix_op!(Add, add, try_add);
+impl_matrix_op!(Sub, sub, try_sub);
+impl_matrix_op!(Mul, mul, try_mul);
+
+impl<T: SimdScalar> Mul<&ComplexSimdVector<T>> for &ComplexSimdMatrix<T> {
+    type Output = ComplexSimdVector<T>;
+
+    fn mul(self, rhs: &ComplexSimdVector<T>) -> Self::Output {
+        self.try_mul_vec(rhs).unwrap_or_else(|e| panic!("{e}"))
+    }
+}
@@ -0,0 +1,191 @@
+use std::ops::Range;
+
+use rayon::prelude::*;
+
+use crate::{
+    backend::{self, cpu::multiversion, Backend, CpuLevel, SimdBackend},
+    simd::Simd,
+};
+
+use super::{
+    matrix_simd::{MatrixOpError, SimdMatrix},
+    scalar::SimdScalar,
+    vector_simd::{SimdVector, LANES},
+};
+
+/// Rows of `C` handed to one rayon task.
+const MC: usize = 64;
+/// Depth of a packed panel of `B`, i.e. how many rows of `B` it covers.
+const KC: usize = 256;
+/// Chunks of `C` covered by one packed panel of `B` (`NC * LANES` columns).
+const NC: usize = 16;
+/// Rows of `C` updated by one micro-kernel call.
+const MR: usize = 4;
+/// Chunks of `C` updated by one micro-kernel call.
+const NR: usize = 2;
+
+impl<T: SimdScalar> SimdMatrix<T> {
+    /// General matrix multiply, `self = alpha * a * b + beta * self`, on the
+    /// current backend. As in BLAS, `beta == 0` overwrites `self` without
+    /// reading it, so NaNs already in `self` do not propagate.
+    pub fn gemm(
+        &mut self,
+        alpha: T,
+        a: &SimdMatrix<T>,
+        b: &SimdMatrix<T>,
+        beta: T,
+    ) -> Result<(), MatrixOpError> {
+        let (m, depth) = a.dimensions();
+        let n = b.row_size;
+        if depth != b.height() {
+            return Err(MatrixOpError::DimensionMismatch {
+                lhs: a.dimensions(),
+                rhs: b.dimensions(),
+            });
+        }
+        if self.dimensions() != (m, n) {
+            return Err(MatrixOpError::DimensionMismatch {
+                lhs: (m, n),
+                rhs: self.dimensions(),
+            });
+        }
+
+        backend::with_current(|i| i.gemm(alpha, a, b, beta, self));
+        Ok(())
+    }
+
+    /// The SIMD implementation of `gemm`, for matrices already known to have
+    /// compatible dimensions.
+    ///
+    /// `b` is copied into `KC x (NC * LANES)` panels laid out in the order
+    /// the micro-kernel reads them. The rows of `self` are then split into
+    /// blocks of `MC` that are updated in parallel, `MR` rows and `NR` chunks
+    /// at a time, with the partial sums kept in registers for the whole panel
+    /// depth.
+    pub(crate) fn blocked_gemm(
+        &mut self,
+        level: CpuLevel,
+        alpha: T,
+        a: &SimdMatrix<T>,
+        b: &SimdMatrix<T>,
+        beta: T,
+    ) {
+        let depth = a.row_size;
+        let n = b.row_size;
+        if beta == T::ZERO {
+            self.matrix
+                .par_iter_mut()
+                .for_each(|i| i.chunks_mut().fill(Simd::splat(T::ZERO)));
+        } else if beta != T::ONE {
+            self.matrix
+                .par_iter_mut()
+                .for_each(|i| SimdBackend.scale(beta, i));
+        }
+
+        let total_chunks = n.div_ceil(LANES);
+        let mut packed = Vec::with_capacity(KC * NC.next_multiple_of(NR));
+        for panel_start in (0..total_chunks).step_by(NC) {
+            let panel_chunks = NC.min(total_chunks - panel_start);
+            for depth_start in (0..depth).step_by(KC) {
+                let panel_depth = KC.min(depth - depth_start);
+                pack_panel(
+                    b,
+                    depth_start,
+                    panel_depth,
+                    panel_start,
+                    panel_chunks,
+                    &mut packed,
+                );
+                self.matrix
+                    .par_chunks_mut(MC)
+                    .enumerate()
+                    .for_each(|(block, c_rows)| {
+                        for (row_group, c_rows) in c_rows.chunks_mut(MR).enumerate() {
+                            let row_start = block * MC + row_group * MR;
+                            let a_rows = &a.matrix[row_start..row_start + c_rows.len()];
+                            for (group, b_panel) in
+                                packed.chunks_exact(panel_depth * NR).enumerate()
+                            {
+                                let chunk_start = panel_start + group * NR;
+                                micro_kernel(
+                                    level,
+                                    alpha,
+                                    a_rows,
+                                    depth_start,
+                                    b_panel,
+                                    c_rows,
+                                    chunk_start..(chunk_start + NR).min(panel_start + panel_chunks),
+                                );
+                            }
+                        }
+                    });
+            }
+        }
+
+        self.matrix
+            .par_iter_mut()
+            .for_each(SimdVector::clear_padding);
+    }
+}
+
+/// Copies rows `depth_start..depth_start + panel_depth` and chunks
+/// `panel_start..panel_start + panel_chunks` of `b` into `packed`, as
+/// consecutive groups of `NR` chunks per row so the micro-kernel reads the
+/// panel front to back. The last group is zero filled to `NR` chunks.
+fn pack_panel<T: SimdScalar>(
+    b: &SimdMatrix<T>,
+    depth_start: usize,
+    panel_depth: usize,
+    panel_start: usize,
+    panel_chunks: usize,
+    packed: &mut Vec<Simd<T, LANES>>,
+) {
+    packed.clear();
+    for group in (0..panel_chunks).step_by(NR) {
+        for row in &b.matrix[depth_start..depth_start + panel_depth] {
+            let chunks = row.chunks();
+            for offset in 0..NR {
+                packed.push(if group + offset < panel_chunks {
+                    chunks[panel_start + group + offset]
+                } else {
+                    Simd::splat(T::ZERO)
+                });
+            }
+        }
+    }
+}
+
+multiversion! {
+    /// Adds `alpha * a_rows[.., depth_start..] * b_panel` to `chunks` of
+    /// `c_rows`, at most `NR` of them.
+    fn micro_kernel<T: SimdScalar>(
+        alpha: T,
+        a_rows: &[SimdVector<T>],
+        depth_start: usize,
+        b_panel: &[Simd<T, LANES>],
+        c_rows: &mut [SimdVector<T>],
+        chunks: Range<usize>,
+    ) {
+        let mut a_slices: [&[T]; MR] = [&[]; MR];
+        for (slice, row) in a_slices.iter_mut().zip(a_rows) {
+            *slice = &row.as_slice()[depth_start..];
+        }
+        let rows = a_rows.len();
+
+        let mut acc = [[Simd::splat(T::ZERO); NR]; MR];
+        for (k, b) in b_panel.chunks_exact(NR).enumerate() {
+            for r in 0..rows {
+                let a = Simd::splat(alpha * a_slices[r][k]);
+                for j in 0..NR {
+                    acc[r][j] = T::simd_add(acc[r][j], T::simd_mul(a, b[j]));
+                }
+            }
+        }
+
+        for (row, acc) in c_rows.iter_mut().zip(acc) {
+            for (chunk, acc) in row.chunks_mut()[chunks.clone()].iter_mut().zip(acc) {
+                *chunk = T::simd_add(*chunk, acc);
+            }
+        }
+    }
+}
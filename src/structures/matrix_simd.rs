@@ -1,18 +1,31 @@
+use std::{
+    borrow::Cow,
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+};
+
+use rand::distributions::Distribution;
 use thiserror::Error;
 
-use super::vector_simd::SimdVector;
+use crate::backend::{self, ElementwiseOp};
+
+use super::{scalar::SimdScalar, vector_simd::SimdVector};
 
 use rayon::prelude::*;
 
-#[derive(Debug, PartialEq)]
-pub struct SimdMatrix {
-    pub matrix: Vec<SimdVector>,
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimdMatrix<T: SimdScalar = f32> {
+    pub matrix: Vec<SimdVector<T>>,
     pub row_size: usize,
 }
 
-pub struct SimdLineIter<'a> {
-    parent: &'a SimdMatrix,
-    linegetfn: fn(&SimdMatrix, usize) -> Option<SimdVector>,
+/// Side length of the square tiles `transpose` copies at a time, small enough
+/// that a tile of the source and of the destination both stay in L1.
+const TRANSPOSE_BLOCK: usize = 32;
+
+/// Iterates over rows, or over the rows of a transposed copy for columns, so
+/// that each line is a single clone rather than an elementwise gather.
+pub struct SimdLineIter<'a, T: SimdScalar = f32> {
+    lines: Cow<'a, [SimdVector<T>]>,
     count: usize,
 }
 
@@ -20,12 +33,25 @@ pub struct SimdLineIter<'a> {
 pub enum MatrixCreationError {
     #[error("Passing a 2D Vector with inconsistent row lengths to Matrix::from() results in failiure because matrices have uniform row length")]
     InconsistentRowLengthErr,
+    #[error("Matrix::from_row_slice() needs exactly rows * columns elements, but was asked for {expected} and given {actual}")]
+    SliceLengthErr { expected: usize, actual: usize },
+}
+
+/// Dimensions are reported as `(rows, columns)`; a vector operand counts as a
+/// single column.
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+pub enum MatrixOpError {
+    #[error("Matrix operation got incompatible dimensions: the left hand side is {}x{} and the right hand side is {}x{}", lhs.0, lhs.1, rhs.0, rhs.1)]
+    DimensionMismatch {
+        lhs: (usize, usize),
+        rhs: (usize, usize),
+    },
 }
 
-impl SimdMatrix {
-    pub fn from(input: Vec<Vec<f32>>) -> Result<SimdMatrix, MatrixCreationError> {
-        let row = input.get(0).unwrap().len();
-        let lengths = input.par_iter().map(Vec::<f32>::len);
+impl<T: SimdScalar> SimdMatrix<T> {
+    pub fn from(input: Vec<Vec<T>>) -> Result<SimdMatrix<T>, MatrixCreationError> {
+        let row = input.first().map_or(0, Vec::len);
+        let lengths = input.par_iter().map(Vec::<T>::len);
         if lengths.all(|i| i == row) {
             let matrix = SimdMatrix {
                 matrix: input
@@ -40,8 +66,8 @@ impl SimdMatrix {
         }
     }
 
-    pub fn from_simd(input: Vec<SimdVector>) -> Result<SimdMatrix, MatrixCreationError> {
-        let row = input.get(0).unwrap().len();
+    pub fn from_simd(input: Vec<SimdVector<T>>) -> Result<SimdMatrix<T>, MatrixCreationError> {
+        let row = input.first().map_or(0, SimdVector::len);
         let lengths = input.par_iter().map(SimdVector::len);
         if lengths.all(|i| i == row) {
             let matrix = SimdMatrix {
@@ -54,6 +80,83 @@ impl SimdMatrix {
         }
     }
 
+    pub fn zeros(rows: usize, columns: usize) -> SimdMatrix<T> {
+        SimdMatrix {
+            matrix: vec![SimdVector::zeros(columns); rows],
+            row_size: columns,
+        }
+    }
+
+    pub fn ones(rows: usize, columns: usize) -> SimdMatrix<T> {
+        Self::from_fn(rows, columns, |_, _| T::ONE)
+    }
+
+    pub fn identity(size: usize) -> SimdMatrix<T> {
+        Self::from_fn(size, size, |i, j| if i == j { T::ONE } else { T::ZERO })
+    }
+
+    /// Square matrix with `diagonal` on its diagonal and zeros elsewhere.
+    pub fn from_diagonal(diagonal: &SimdVector<T>) -> SimdMatrix<T> {
+        let mut matrix = Self::zeros(diagonal.len(), diagonal.len());
+        matrix
+            .matrix
+            .iter_mut()
+            .zip(diagonal.iter())
+            .enumerate()
+            .for_each(|(i, (row, value))| row[i] = value);
+        matrix
+    }
+
+    /// Builds a matrix whose element at row `i`, column `j` is `f(i, j)`. Rows
+    /// are generated in parallel.
+    pub fn from_fn(
+        rows: usize,
+        columns: usize,
+        f: impl Fn(usize, usize) -> T + Sync,
+    ) -> SimdMatrix<T> {
+        SimdMatrix {
+            matrix: (0..rows)
+                .into_par_iter()
+                .map(|i| SimdVector::from_vector((0..columns).map(|j| f(i, j)).collect()))
+                .collect(),
+            row_size: columns,
+        }
+    }
+
+    /// Builds a matrix from `rows * columns` elements in row-major order.
+    pub fn from_row_slice(
+        rows: usize,
+        columns: usize,
+        slice: &[T],
+    ) -> Result<SimdMatrix<T>, MatrixCreationError> {
+        if slice.len() != rows * columns {
+            return Err(MatrixCreationError::SliceLengthErr {
+                expected: rows * columns,
+                actual: slice.len(),
+            });
+        }
+        Ok(SimdMatrix {
+            matrix: (0..rows)
+                .map(|i| SimdVector::from_slice(&slice[i * columns..(i + 1) * columns]))
+                .collect(),
+            row_size: columns,
+        })
+    }
+
+    /// Fills a matrix with samples drawn from `distribution`, e.g.
+    /// `rand::distributions::Uniform::new(-1., 1.)` or `rand::distributions::Standard`.
+    pub fn random(
+        rows: usize,
+        columns: usize,
+        distribution: impl Distribution<T>,
+    ) -> SimdMatrix<T> {
+        let samples: Vec<T> = distribution
+            .sample_iter(rand::thread_rng())
+            .take(rows * columns)
+            .collect();
+        Self::from_row_slice(rows, columns, &samples).unwrap()
+    }
+
     pub fn is_square(&self) -> bool {
         self.row_size == self.matrix.len()
     }
@@ -62,8 +165,137 @@ impl SimdMatrix {
         self.matrix.len()
     }
 
-    pub fn column(&self, column: usize) -> Option<SimdVector> {
-        let column_slice: Vec<f32> = self
+    pub fn dimensions(&self) -> (usize, usize) {
+        (self.height(), self.row_size)
+    }
+
+    pub fn try_add(&self, rhs: &SimdMatrix<T>) -> Result<SimdMatrix<T>, MatrixOpError> {
+        self.check_same_dimensions(rhs)?;
+        Ok(self + rhs)
+    }
+
+    pub fn try_sub(&self, rhs: &SimdMatrix<T>) -> Result<SimdMatrix<T>, MatrixOpError> {
+        self.check_same_dimensions(rhs)?;
+        Ok(self - rhs)
+    }
+
+    /// Elementwise product, one SIMD multiplication per chunk.
+    pub fn try_hadamard(&self, rhs: &SimdMatrix<T>) -> Result<SimdMatrix<T>, MatrixOpError> {
+        self.check_same_dimensions(rhs)?;
+        Ok(backend::with_current(|backend| {
+            self.zip_rows(rhs, |i, j| backend.elementwise(ElementwiseOp::Mul, i, j))
+        }))
+    }
+
+    /// Elementwise quotient, one SIMD division per chunk.
+    pub fn try_elem_div(&self, rhs: &SimdMatrix<T>) -> Result<SimdMatrix<T>, MatrixOpError> {
+        self.check_same_dimensions(rhs)?;
+        Ok(backend::with_current(|backend| {
+            self.zip_rows(rhs, |i, j| backend.elementwise(ElementwiseOp::Div, i, j))
+        }))
+    }
+
+    /// Applies `f` to every element, with the rows handled in parallel.
+    pub fn map(&self, f: impl Fn(T) -> T + Sync) -> SimdMatrix<T> {
+        let mut out = self.clone();
+        out.matrix
+            .par_iter_mut()
+            .for_each(|row| row.as_mut_slice().iter_mut().for_each(|i| *i = f(*i)));
+        out
+    }
+
+    /// Combines each element with the matching element of `rhs` through `f`.
+    pub fn try_zip_map(
+        &self,
+        rhs: &SimdMatrix<T>,
+        f: impl Fn(T, T) -> T + Sync,
+    ) -> Result<SimdMatrix<T>, MatrixOpError> {
+        self.check_same_dimensions(rhs)?;
+        Ok(self.zip_rows(rhs, |i, j| {
+            for (a, &b) in i.as_mut_slice().iter_mut().zip(j.as_slice()) {
+                *a = f(*a, b);
+            }
+        }))
+    }
+
+    fn zip_rows(
+        &self,
+        rhs: &SimdMatrix<T>,
+        f: impl Fn(&mut SimdVector<T>, &SimdVector<T>) + Sync,
+    ) -> SimdMatrix<T> {
+        let mut out = self.clone();
+        out.matrix
+            .par_iter_mut()
+            .zip(rhs.matrix.par_iter())
+            .for_each(|(i, j)| f(i, j));
+        out
+    }
+
+    /// Matrix product `self * rhs`, computed with `gemm`.
+    pub fn try_mul(&self, rhs: &SimdMatrix<T>) -> Result<SimdMatrix<T>, MatrixOpError> {
+        let mut out = SimdMatrix {
+            matrix: vec![SimdVector::zeros(rhs.row_size); self.height()],
+            row_size: rhs.row_size,
+        };
+        out.gemm(T::ONE, self, rhs, T::ZERO)?;
+        Ok(out)
+    }
+
+    /// Vector-matrix product `lhsᵀ * self`, accumulated as a sum of scaled
+    /// rows so it never has to gather a column.
+    pub fn try_vec_mul(&self, lhs: &SimdVector<T>) -> Result<SimdVector<T>, MatrixOpError> {
+        if lhs.len() != self.height() {
+            return Err(MatrixOpError::DimensionMismatch {
+                lhs: (1, lhs.len()),
+                rhs: self.dimensions(),
+            });
+        }
+        let zero = SimdVector::zeros(self.row_size);
+        Ok(backend::with_current(|backend| {
+            self.matrix
+                .par_iter()
+                .zip(lhs.as_slice().par_iter())
+                .fold(
+                    || zero.clone(),
+                    |mut acc, (row, factor)| {
+                        backend.axpy(*factor, row, &mut acc);
+                        acc
+                    },
+                )
+                .reduce(
+                    || zero.clone(),
+                    |mut acc, i| {
+                        backend.elementwise(ElementwiseOp::Add, &mut acc, &i);
+                        acc
+                    },
+                )
+        }))
+    }
+
+    /// Matrix-vector product `self * rhs`, on the current backend.
+    pub fn try_mul_vec(&self, rhs: &SimdVector<T>) -> Result<SimdVector<T>, MatrixOpError> {
+        if self.row_size != rhs.len() {
+            return Err(MatrixOpError::DimensionMismatch {
+                lhs: self.dimensions(),
+                rhs: (rhs.len(), 1),
+            });
+        }
+        Ok(backend::with_current(|i| i.gemv(self, rhs)))
+    }
+
+    fn check_same_dimensions(&self, rhs: &SimdMatrix<T>) -> Result<(), MatrixOpError> {
+        if self.dimensions() == rhs.dimensions() {
+            Ok(())
+        } else {
+            Err(MatrixOpError::DimensionMismatch {
+                lhs: self.dimensions(),
+                rhs: rhs.dimensions(),
+            })
+        }
+    }
+
+    pub fn column(&self, column: usize) -> Option<SimdVector<T>> {
+        let column_slice: Vec<T> = self
             .matrix
             .par_iter()
             .filter_map(|i| i.get(column))
@@ -75,36 +307,251 @@ impl SimdMatrix {
         }
     }
 
-    pub fn row(&self, row: usize) -> Option<SimdVector> {
+    pub fn row(&self, row: usize) -> Option<SimdVector<T>> {
         self.matrix.get(row).cloned()
     }
 
-    pub fn to_vector(&self) -> Vec<Vec<f32>> {
+    pub fn to_vector(&self) -> Vec<Vec<T>> {
         self.matrix.par_iter().map(|i| i.to_vector()).collect()
     }
 
-    pub fn iter_column(&self) -> SimdLineIter {
+    pub fn iter_column(&self) -> SimdLineIter<'_, T> {
         SimdLineIter {
-            parent: (&self),
-            linegetfn: SimdMatrix::column,
-            count: (0),
+            lines: Cow::Owned(self.transpose().matrix),
+            count: 0,
         }
     }
 
-    pub fn iter_row(&self) -> SimdLineIter {
+    pub fn iter_row(&self) -> SimdLineIter<'_, T> {
         SimdLineIter {
-            parent: &self,
-            linegetfn: SimdMatrix::row,
+            lines: Cow::Borrowed(&self.matrix),
             count: 0,
         }
     }
+
+    /// Returns the transpose, copied tile by tile so that both the reads and
+    /// the writes stay within a few cache lines. Output rows are filled in
+    /// parallel.
+    pub fn transpose(&self) -> SimdMatrix<T> {
+        let (height, width) = self.dimensions();
+        let mut matrix = vec![SimdVector::zeros(height); width];
+        matrix
+            .par_chunks_mut(TRANSPOSE_BLOCK)
+            .enumerate()
+            .for_each(|(block, out_rows)| {
+                let column_start = block * TRANSPOSE_BLOCK;
+                for row_start in (0..height).step_by(TRANSPOSE_BLOCK) {
+                    let rows = &self.matrix[row_start..(row_start + TRANSPOSE_BLOCK).min(height)];
+                    for (offset, out_row) in out_rows.iter_mut().enumerate() {
+                        let out_row = &mut out_row[row_start..row_start + rows.len()];
+                        for (out, row) in out_row.iter_mut().zip(rows) {
+                            *out = row[column_start + offset];
+                        }
+                    }
+                }
+            });
+        SimdMatrix {
+            matrix,
+            row_size: height,
+        }
+    }
+
+    /// Transposes in place. Square matrices swap elements across the diagonal
+    /// tile by tile without allocating; other shapes fall back to `transpose`.
+    pub fn transpose_mut(&mut self) {
+        if !self.is_square() {
+            *self = self.transpose();
+            return;
+        }
+        let size = self.row_size;
+        for row_start in (0..size).step_by(TRANSPOSE_BLOCK) {
+            let row_end = (row_start + TRANSPOSE_BLOCK).min(size);
+            for column_start in (row_start..size).step_by(TRANSPOSE_BLOCK) {
+                let column_end = (column_start + TRANSPOSE_BLOCK).min(size);
+                for i in row_start..row_end {
+                    for j in column_start.max(i + 1)..column_end {
+                        let (upper, lower) = self.matrix.split_at_mut(j);
+                        std::mem::swap(&mut upper[i][j], &mut lower[0][i]);
+                    }
+                }
+            }
+        }
+    }
 }
 
-impl<'a> Iterator for SimdLineIter<'a> {
-    type Item = SimdVector;
+impl<'a, T: SimdScalar> Iterator for SimdLineIter<'a, T> {
+    type Item = SimdVector<T>;
     fn next(&mut self) -> Option<Self::Item> {
-        let val = (self.linegetfn)(self.parent, self.count);
+        let val = self.lines.get(self.count).cloned();
         self.count += 1;
         val
     }
 }
+
+// As with `SimdVector`, the operators panic on mismatched dimensions; the
+// `try_` methods return a `MatrixOpError` instead. The elementwise operators
+// work row by row through the in-place `*Assign<&SimdMatrix>` form.
+macro_rules! impl_elementwise_op {
+    ($op:ident, $fn:ident, $op_assign:ident, $fn_assign:ident, $kind:ident) => {
+        impl<T: SimdScalar> $op_assign<&SimdMatrix<T>> for SimdMatrix<T> {
+            fn $fn_assign(&mut self, rhs: &SimdMatrix<T>) {
+                if let Err(e) = self.check_same_dimensions(rhs) {
+                    panic!("{e}");
+                }
+                backend::with_current(|backend| {
+                    self.matrix
+                        .par_iter_mut()
+                        .zip(rhs.matrix.par_iter())
+                        .for_each(|(i, j)| backend.elementwise(ElementwiseOp::$kind, i, j))
+                });
+            }
+        }
+
+        impl<T: SimdScalar> $op_assign for SimdMatrix<T> {
+            fn $fn_assign(&mut self, rhs: SimdMatrix<T>) {
+                self.$fn_assign(&rhs);
+            }
+        }
+
+        impl<T: SimdScalar> $op<&SimdMatrix<T>> for SimdMatrix<T> {
+            type Output = SimdMatrix<T>;
+
+            fn $fn(mut self, rhs: &SimdMatrix<T>) -> Self::Output {
+                self.$fn_assign(rhs);
+                self
+            }
+        }
+
+        impl<T: SimdScalar> $op for SimdMatrix<T> {
+            type Output = SimdMatrix<T>;
+
+            fn $fn(self, rhs: SimdMatrix<T>) -> Self::Output {
+                $op::$fn(self, &rhs)
+            }
+        }
+
+        impl<T: SimdScalar> $op<&SimdMatrix<T>> for &SimdMatrix<T> {
+            type Output = SimdMatrix<T>;
+
+            fn $fn(self, rhs: &SimdMatrix<T>) -> Self::Output {
+                let mut out = self.clone();
+                out.$fn_assign(rhs);
+                out
+            }
+        }
+    };
+}
+
+impl_elementwise_op!(Add, add, AddAssign, add_assign, Add);
+impl_elementwise_op!(Sub, sub, SubAssign, sub_assign, Sub);
+
+impl<T: SimdScalar> MulAssign<T> for SimdMatrix<T> {
+    fn mul_assign(&mut self, rhs: T) {
+        backend::with_current(|backend| {
+            self.matrix
+                .par_iter_mut()
+                .for_each(|i| backend.scale(rhs, i))
+        });
+    }
+}
+
+impl<T: SimdScalar> DivAssign<T> for SimdMatrix<T> {
+    fn div_assign(&mut self, rhs: T) {
+        self.matrix.par_iter_mut().for_each(|i| *i /= rhs);
+    }
+}
+
+impl<T: SimdScalar> Mul<&SimdMatrix<T>> for &SimdMatrix<T> {
+    type Output = SimdMatrix<T>;
+
+    fn mul(self, rhs: &SimdMatrix<T>) -> Self::Output {
+        self.try_mul(rhs).unwrap_or_else(|e| panic!("{e}"))
+    }
+}
+
+impl<T: SimdScalar> Mul for SimdMatrix<T> {
+    type Output = SimdMatrix<T>;
+
+    fn mul(self, rhs: SimdMatrix<T>) -> Self::Output {
+        &self * &rhs
+    }
+}
+
+impl<T: SimdScalar> Mul<&SimdVector<T>> for &SimdMatrix<T> {
+    type Output = SimdVector<T>;
+
+    fn mul(self, rhs: &SimdVector<T>) -> Self::Output {
+        self.try_mul_vec(rhs).unwrap_or_else(|e| panic!("{e}"))
+    }
+}
+
+impl<T: SimdScalar> Mul<&SimdMatrix<T>> for &SimdVector<T> {
+    type Output = SimdVector<T>;
+
+    fn mul(self, rhs: &SimdMatrix<T>) -> Self::Output {
+        rhs.try_vec_mul(self).unwrap_or_else(|e| panic!("{e}"))
+    }
+}
+
+impl<T: SimdScalar> Mul<SimdVector<T>> for SimdMatrix<T> {
+    type Output = SimdVector<T>;
+
+    fn mul(self, rhs: SimdVector<T>) -> Self::Output {
+        &self * &rhs
+    }
+}
+
+impl<T: SimdScalar> Mul<T> for SimdMatrix<T> {
+    type Output = SimdMatrix<T>;
+
+    fn mul(mut self, rhs: T) -> Self::Output {
+        self *= rhs;
+        self
+    }
+}
+
+impl<T: SimdScalar> Mul<T> for &SimdMatrix<T> {
+    type Output = SimdMatrix<T>;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        let mut out = self.clone();
+        out *= rhs;
+        out
+    }
+}
+
+impl<T: SimdScalar> Div<T> for &SimdMatrix<T> {
+    type Output = SimdMatrix<T>;
+
+    fn div(self, rhs: T) -> Self::Output {
+        let mut out = self.clone();
+        out /= rhs;
+        out
+    }
+}
+
+impl<T: SimdScalar> Div<T> for SimdMatrix<T> {
+    type Output = SimdMatrix<T>;
+
+    fn div(mut self, rhs: T) -> Self::Output {
+        self /= rhs;
+        self
+    }
+}
+
+impl<T: SimdScalar> Neg for SimdMatrix<T> {
+    type Output = SimdMatrix<T>;
+
+    fn neg(mut self) -> Self::Output {
+        self.matrix.par_iter_mut().for_each(SimdVector::negate);
+        self
+    }
+}
+
+impl<T: SimdScalar> Neg for &SimdMatrix<T> {
+    type Output = SimdMatrix<T>;
+
+    fn neg(self) -> Self::Output {
+        -self.clone()
+    }
+}
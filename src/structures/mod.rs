@@ -1,3 +1,7 @@
+pub mod complex_simd;
+pub mod gemm;
 pub mod matrix_simd;
+pub mod scalar;
 pub mod simd_async;
+pub mod sparse;
 pub mod vector_simd;
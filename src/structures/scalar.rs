@@ -0,0 +1,211 @@
+use std::{
+    fmt::Debug,
+    ops::{Add, Div, Mul, Sub},
+};
+
+#[cfg(not(feature = "nightly-simd"))]
+use std::ops::Neg;
+#[cfg(feature = "nightly-simd")]
+use std::simd::num::{SimdFloat, SimdInt, SimdUint};
+
+use crate::simd::{LaneCount, Simd, SimdElement, SupportedLaneCount};
+
+/// Element types that can be stored in a [`SimdVector`](super::vector_simd::SimdVector).
+///
+/// `std::simd` only implements arithmetic on `Simd<T, N>` for concrete element
+/// types, so the lane kernels are exposed here as associated functions and
+/// implemented once per primitive by `impl_simd_scalar!`. Without the
+/// `nightly-simd` feature the same kernels run lane by lane, with the same
+/// wrapping integer semantics.
+pub trait SimdScalar:
+    SimdElement
+    + Default
+    + Debug
+    + PartialEq
+    + PartialOrd
+    + Send
+    + Sync
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + 'static
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn simd_add<const N: usize>(lhs: Simd<Self, N>, rhs: Simd<Self, N>) -> Simd<Self, N>
+    where
+        LaneCount<N>: SupportedLaneCount;
+
+    fn simd_sub<const N: usize>(lhs: Simd<Self, N>, rhs: Simd<Self, N>) -> Simd<Self, N>
+    where
+        LaneCount<N>: SupportedLaneCount;
+
+    fn simd_mul<const N: usize>(lhs: Simd<Self, N>, rhs: Simd<Self, N>) -> Simd<Self, N>
+    where
+        LaneCount<N>: SupportedLaneCount;
+
+    fn simd_div<const N: usize>(lhs: Simd<Self, N>, rhs: Simd<Self, N>) -> Simd<Self, N>
+    where
+        LaneCount<N>: SupportedLaneCount;
+
+    /// Lanewise negation. Unsigned lanes wrap, like the other integer kernels.
+    fn simd_neg<const N: usize>(input: Simd<Self, N>) -> Simd<Self, N>
+    where
+        LaneCount<N>: SupportedLaneCount;
+
+    fn simd_reduce_sum<const N: usize>(input: Simd<Self, N>) -> Self
+    where
+        LaneCount<N>: SupportedLaneCount;
+}
+
+#[cfg(feature = "nightly-simd")]
+macro_rules! impl_simd_scalar {
+    ($($t:ty => $zero:expr, $one:expr, $neg:expr);* $(;)?) => {
+        $(
+            impl SimdScalar for $t {
+                const ZERO: Self = $zero;
+                const ONE: Self = $one;
+
+                #[inline]
+                fn simd_add<const N: usize>(lhs: Simd<Self, N>, rhs: Simd<Self, N>) -> Simd<Self, N>
+                where
+                    LaneCount<N>: SupportedLaneCount,
+                {
+                    lhs + rhs
+                }
+
+                #[inline]
+                fn simd_sub<const N: usize>(lhs: Simd<Self, N>, rhs: Simd<Self, N>) -> Simd<Self, N>
+                where
+                    LaneCount<N>: SupportedLaneCount,
+                {
+                    lhs - rhs
+                }
+
+                #[inline]
+                fn simd_mul<const N: usize>(lhs: Simd<Self, N>, rhs: Simd<Self, N>) -> Simd<Self, N>
+                where
+                    LaneCount<N>: SupportedLaneCount,
+                {
+                    lhs * rhs
+                }
+
+                #[inline]
+                fn simd_div<const N: usize>(lhs: Simd<Self, N>, rhs: Simd<Self, N>) -> Simd<Self, N>
+                where
+                    LaneCount<N>: SupportedLaneCount,
+                {
+                    lhs / rhs
+                }
+
+                #[inline]
+                fn simd_neg<const N: usize>(input: Simd<Self, N>) -> Simd<Self, N>
+                where
+                    LaneCount<N>: SupportedLaneCount,
+                {
+                    ($neg)(input)
+                }
+
+                #[inline]
+                fn simd_reduce_sum<const N: usize>(input: Simd<Self, N>) -> Self
+                where
+                    LaneCount<N>: SupportedLaneCount,
+                {
+                    input.reduce_sum()
+                }
+            }
+        )*
+    };
+}
+
+#[cfg(feature = "nightly-simd")]
+impl_simd_scalar! {
+    f32 => 0., 1., |i: Simd<f32, N>| -i;
+    f64 => 0., 1., |i: Simd<f64, N>| -i;
+    i8 => 0, 1, |i: Simd<i8, N>| -i;
+    i16 => 0, 1, |i: Simd<i16, N>| -i;
+    i32 => 0, 1, |i: Simd<i32, N>| -i;
+    i64 => 0, 1, |i: Simd<i64, N>| -i;
+    u8 => 0, 1, |i: Simd<u8, N>| Simd::splat(0) - i;
+    u16 => 0, 1, |i: Simd<u16, N>| Simd::splat(0) - i;
+    u32 => 0, 1, |i: Simd<u32, N>| Simd::splat(0) - i;
+    u64 => 0, 1, |i: Simd<u64, N>| Simd::splat(0) - i;
+}
+
+/// The stable counterpart of the macro above. Each kernel is written per
+/// lane, and integers use the wrapping operations `std::simd` has.
+#[cfg(not(feature = "nightly-simd"))]
+macro_rules! impl_simd_scalar {
+    ($($t:ty => $zero:expr, $one:expr, $add:expr, $sub:expr, $mul:expr, $div:expr, $neg:expr);* $(;)?) => {
+        $(
+            impl SimdScalar for $t {
+                const ZERO: Self = $zero;
+                const ONE: Self = $one;
+
+                #[inline]
+                fn simd_add<const N: usize>(lhs: Simd<Self, N>, rhs: Simd<Self, N>) -> Simd<Self, N>
+                where
+                    LaneCount<N>: SupportedLaneCount,
+                {
+                    lhs.zip_lanes(rhs, $add)
+                }
+
+                #[inline]
+                fn simd_sub<const N: usize>(lhs: Simd<Self, N>, rhs: Simd<Self, N>) -> Simd<Self, N>
+                where
+                    LaneCount<N>: SupportedLaneCount,
+                {
+                    lhs.zip_lanes(rhs, $sub)
+                }
+
+                #[inline]
+                fn simd_mul<const N: usize>(lhs: Simd<Self, N>, rhs: Simd<Self, N>) -> Simd<Self, N>
+                where
+                    LaneCount<N>: SupportedLaneCount,
+                {
+                    lhs.zip_lanes(rhs, $mul)
+                }
+
+                #[inline]
+                fn simd_div<const N: usize>(lhs: Simd<Self, N>, rhs: Simd<Self, N>) -> Simd<Self, N>
+                where
+                    LaneCount<N>: SupportedLaneCount,
+                {
+                    lhs.zip_lanes(rhs, $div)
+                }
+
+                #[inline]
+                fn simd_neg<const N: usize>(input: Simd<Self, N>) -> Simd<Self, N>
+                where
+                    LaneCount<N>: SupportedLaneCount,
+                {
+                    input.map_lanes($neg)
+                }
+
+                #[inline]
+                fn simd_reduce_sum<const N: usize>(input: Simd<Self, N>) -> Self
+                where
+                    LaneCount<N>: SupportedLaneCount,
+                {
+                    input.to_array().into_iter().fold($zero, $add)
+                }
+            }
+        )*
+    };
+}
+
+#[cfg(not(feature = "nightly-simd"))]
+impl_simd_scalar! {
+    f32 => 0., 1., f32::add, f32::sub, f32::mul, f32::div, f32::neg;
+    f64 => 0., 1., f64::add, f64::sub, f64::mul, f64::div, f64::neg;
+    i8 => 0, 1, i8::wrapping_add, i8::wrapping_sub, i8::wrapping_mul, i8::wrapping_div, i8::wrapping_neg;
+    i16 => 0, 1, i16::wrapping_add, i16::wrapping_sub, i16::wrapping_mul, i16::wrapping_div, i16::wrapping_neg;
+    i32 => 0, 1, i32::wrapping_add, i32::wrapping_sub, i32::wrapping_mul, i32::wrapping_div, i32::wrapping_neg;
+    i64 => 0, 1, i64::wrapping_add, i64::wrapping_sub, i64::wrapping_mul, i64::wrapping_div, i64::wrapping_neg;
+    u8 => 0, 1, u8::wrapping_add, u8::wrapping_sub, u8::wrapping_mul, u8::wrapping_div, u8::wrapping_neg;
+    u16 => 0, 1, u16::wrapping_add, u16::wrapping_sub, u16::wrapping_mul, u16::wrapping_div, u16::wrapping_neg;
+    u32 => 0, 1, u32::wrapping_add, u32::wrapping_sub, u32::wrapping_mul, u32::wrapping_div, u32::wrapping_neg;
+    u64 => 0, 1, u64::wrapping_add, u64::wrapping_sub, u64::wrapping_mul, u64::wrapping_div, u64::wrapping_neg;
+}
@@ -0,0 +1,761 @@
+use std::{borrow::Cow, ops::Range};
+
+use rayon::prelude::*;
+use thiserror::Error;
+
+use crate::{backend, simd::Simd};
+
+use super::{
+    matrix_simd::{MatrixOpError, SimdMatrix},
+    scalar::SimdScalar,
+    vector_simd::{SimdVector, LANES},
+};
+
+/// Coordinate (triplet) storage, meant for assembling a matrix entry by entry
+/// before converting it to [`CsrMatrix`] or [`CscMatrix`] for computation.
+///
+/// Entries can be pushed in any order and the same position can be pushed
+/// more than once; duplicates are summed on conversion, which is what finite
+/// element assembly wants.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CooMatrix<T: SimdScalar = f32> {
+    rows: usize,
+    columns: usize,
+    row_indices: Vec<usize>,
+    column_indices: Vec<usize>,
+    values: Vec<T>,
+}
+
+/// Compressed sparse row storage.
+///
+/// The stored entries of row `i` are `indices[offsets[i]..offsets[i + 1]]`
+/// with the matching `values`, and the column indices within a row are
+/// strictly increasing.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsrMatrix<T: SimdScalar = f32> {
+    rows: usize,
+    columns: usize,
+    offsets: Vec<usize>,
+    indices: Vec<usize>,
+    values: Vec<T>,
+}
+
+/// Compressed sparse column storage.
+///
+/// The compressed columns of a matrix are exactly the compressed rows of its
+/// transpose, so this wraps the [`CsrMatrix`] of the transpose and maps each
+/// operation onto its row-wise counterpart.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CscMatrix<T: SimdScalar = f32> {
+    transpose: CsrMatrix<T>,
+}
+
+/// Iterates over the rows of a sparse matrix as dense vectors, or over the
+/// rows of a transposed copy for columns, like `SimdLineIter`.
+pub struct SparseLineIter<'a, T: SimdScalar = f32> {
+    lines: Cow<'a, CsrMatrix<T>>,
+    count: usize,
+}
+
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+pub enum SparseError {
+    #[error("Entry ({row}, {column}) is outside of a {rows}x{columns} matrix")]
+    OutOfBounds {
+        row: usize,
+        column: usize,
+        rows: usize,
+        columns: usize,
+    },
+    #[error("Sparse storage needs one index per value, but was given {indices} indices and {values} values")]
+    LengthMismatch { indices: usize, values: usize },
+    #[error("Compressed offsets need one entry per line plus one, starting at zero, never decreasing and ending at the number of stored values")]
+    MalformedOffsets,
+    #[error("Indices within line {line} are not strictly increasing")]
+    UnsortedIndices { line: usize },
+}
+
+impl<T: SimdScalar> CooMatrix<T> {
+    pub fn new(rows: usize, columns: usize) -> CooMatrix<T> {
+        CooMatrix {
+            rows,
+            columns,
+            row_indices: vec![],
+            column_indices: vec![],
+            values: vec![],
+        }
+    }
+
+    pub fn from_triplets(
+        rows: usize,
+        columns: usize,
+        triplets: impl IntoIterator<Item = (usize, usize, T)>,
+    ) -> Result<CooMatrix<T>, SparseError> {
+        let mut matrix = CooMatrix::new(rows, columns);
+        for (row, column, value) in triplets {
+            matrix.push(row, column, value)?;
+        }
+        Ok(matrix)
+    }
+
+    pub fn from_dense(dense: &SimdMatrix<T>) -> CooMatrix<T> {
+        CsrMatrix::from_dense(dense).to_coo()
+    }
+
+    /// Adds an entry, which is summed with any other entries at the same
+    /// position on conversion.
+    pub fn push(&mut self, row: usize, column: usize, value: T) -> Result<(), SparseError> {
+        if row >= self.rows || column >= self.columns {
+            return Err(SparseError::OutOfBounds {
+                row,
+                column,
+                rows: self.rows,
+                columns: self.columns,
+            });
+        }
+        self.row_indices.push(row);
+        self.column_indices.push(column);
+        self.values.push(value);
+        Ok(())
+    }
+
+    pub fn dimensions(&self) -> (usize, usize) {
+        (self.rows, self.columns)
+    }
+
+    /// Number of pushed entries, counting duplicates separately.
+    pub fn nnz(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (usize, usize, T)> + '_ {
+        self.row_indices
+            .iter()
+            .zip(&self.column_indices)
+            .zip(&self.values)
+            .map(|((&row, &column), &value)| (row, column, value))
+    }
+
+    pub fn to_csr(&self) -> CsrMatrix<T> {
+        compress(
+            self.rows,
+            self.columns,
+            &self.row_indices,
+            &self.column_indices,
+            &self.values,
+        )
+    }
+
+    pub fn to_csc(&self) -> CscMatrix<T> {
+        CscMatrix {
+            transpose: compress(
+                self.columns,
+                self.rows,
+                &self.column_indices,
+                &self.row_indices,
+                &self.values,
+            ),
+        }
+    }
+
+    pub fn to_dense(&self) -> SimdMatrix<T> {
+        let mut dense = SimdMatrix::zeros(self.rows, self.columns);
+        for (row, column, value) in self.iter() {
+            let entry = &mut dense.matrix[row][column];
+            *entry = *entry + value;
+        }
+        dense
+    }
+}
+
+/// Sorts triplets into compressed rows of a `rows x columns` matrix, summing
+/// duplicates.
+fn compress<T: SimdScalar>(
+    rows: usize,
+    columns: usize,
+    row_indices: &[usize],
+    column_indices: &[usize],
+    values: &[T],
+) -> CsrMatrix<T> {
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    order.sort_unstable_by_key(|&i| (row_indices[i], column_indices[i]));
+
+    let mut offsets = vec![0; rows + 1];
+    let mut indices = Vec::with_capacity(order.len());
+    let mut compressed: Vec<T> = Vec::with_capacity(order.len());
+    let mut previous = None;
+    for i in order {
+        let entry = (row_indices[i], column_indices[i]);
+        if previous == Some(entry) {
+            let last = compressed.last_mut().unwrap();
+            *last = *last + values[i];
+        } else {
+            offsets[entry.0 + 1] += 1;
+            indices.push(entry.1);
+            compressed.push(values[i]);
+            previous = Some(entry);
+        }
+    }
+    prefix_sum(&mut offsets);
+    CsrMatrix {
+        rows,
+        columns,
+        offsets,
+        indices,
+        values: compressed,
+    }
+}
+
+fn prefix_sum(counts: &mut [usize]) {
+    let mut total = 0;
+    for i in counts {
+        total += *i;
+        *i = total;
+    }
+}
+
+impl<T: SimdScalar> CsrMatrix<T> {
+    /// Builds a matrix from raw compressed rows, checking every invariant
+    /// listed on the type.
+    pub fn try_new(
+        rows: usize,
+        columns: usize,
+        offsets: Vec<usize>,
+        indices: Vec<usize>,
+        values: Vec<T>,
+    ) -> Result<CsrMatrix<T>, SparseError> {
+        if indices.len() != values.len() {
+            return Err(SparseError::LengthMismatch {
+                indices: indices.len(),
+                values: values.len(),
+            });
+        }
+        if offsets.len() != rows + 1
+            || offsets[0] != 0
+            || offsets[rows] != indices.len()
+            || offsets.windows(2).any(|i| i[0] > i[1])
+        {
+            return Err(SparseError::MalformedOffsets);
+        }
+        for (row, line) in offsets.windows(2).enumerate() {
+            let line = &indices[line[0]..line[1]];
+            if line.windows(2).any(|i| i[0] >= i[1]) {
+                return Err(SparseError::UnsortedIndices { line: row });
+            }
+            if let Some(&column) = line.last().filter(|&&i| i >= columns) {
+                return Err(SparseError::OutOfBounds {
+                    row,
+                    column,
+                    rows,
+                    columns,
+                });
+            }
+        }
+        Ok(CsrMatrix {
+            rows,
+            columns,
+            offsets,
+            indices,
+            values,
+        })
+    }
+
+    /// Compresses the nonzero elements of a dense matrix.
+    pub fn from_dense(dense: &SimdMatrix<T>) -> CsrMatrix<T> {
+        let mut offsets = Vec::with_capacity(dense.height() + 1);
+        let mut indices = vec![];
+        let mut values = vec![];
+        offsets.push(0);
+        for row in &dense.matrix {
+            for (column, value) in row.iter().enumerate() {
+                if value != T::ZERO {
+                    indices.push(column);
+                    values.push(value);
+                }
+            }
+            offsets.push(indices.len());
+        }
+        CsrMatrix {
+            rows: dense.height(),
+            columns: dense.row_size,
+            offsets,
+            indices,
+            values,
+        }
+    }
+
+    pub fn to_dense(&self) -> SimdMatrix<T> {
+        SimdMatrix {
+            matrix: (0..self.rows)
+                .into_par_iter()
+                .map(|i| self.dense_row(i))
+                .collect(),
+            row_size: self.columns,
+        }
+    }
+
+    pub fn to_coo(&self) -> CooMatrix<T> {
+        CooMatrix {
+            rows: self.rows,
+            columns: self.columns,
+            row_indices: (0..self.rows)
+                .flat_map(|i| self.line(i).map(move |_| i))
+                .collect(),
+            column_indices: self.indices.clone(),
+            values: self.values.clone(),
+        }
+    }
+
+    pub fn to_csc(&self) -> CscMatrix<T> {
+        CscMatrix {
+            transpose: self.transpose(),
+        }
+    }
+
+    pub fn dimensions(&self) -> (usize, usize) {
+        (self.rows, self.columns)
+    }
+
+    pub fn nnz(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn offsets(&self) -> &[usize] {
+        &self.offsets
+    }
+
+    pub fn indices(&self) -> &[usize] {
+        &self.indices
+    }
+
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+
+    /// The column indices and values stored in `row`.
+    pub fn row_entries(&self, row: usize) -> (&[usize], &[T]) {
+        let line = self.line(row);
+        (&self.indices[line.clone()], &self.values[line])
+    }
+
+    /// The element at `(row, column)`, which is zero unless it is stored, or
+    /// `None` outside of the matrix.
+    pub fn get(&self, row: usize, column: usize) -> Option<T> {
+        if row >= self.rows || column >= self.columns {
+            return None;
+        }
+        let (indices, values) = self.row_entries(row);
+        Some(
+            indices
+                .binary_search(&column)
+                .map_or(T::ZERO, |i| values[i]),
+        )
+    }
+
+    /// The entries on and below the diagonal.
+    pub fn lower_triangle(&self) -> CsrMatrix<T> {
+        let mut offsets = Vec::with_capacity(self.rows + 1);
+        let mut indices = vec![];
+        let mut values = vec![];
+        offsets.push(0);
+        for row in 0..self.rows {
+            let (columns, line) = self.row_entries(row);
+            let end = columns.partition_point(|&i| i <= row);
+            indices.extend_from_slice(&columns[..end]);
+            values.extend_from_slice(&line[..end]);
+            offsets.push(indices.len());
+        }
+        CsrMatrix {
+            rows: self.rows,
+            columns: self.columns,
+            offsets,
+            indices,
+            values,
+        }
+    }
+
+    /// The offsets and indices alongside mutable values, for factorizations
+    /// that keep the sparsity pattern.
+    pub(crate) fn parts_mut(&mut self) -> (&[usize], &[usize], &mut [T]) {
+        (&self.offsets, &self.indices, &mut self.values)
+    }
+
+    pub fn dense_row(&self, row: usize) -> SimdVector<T> {
+        let mut dense = SimdVector::zeros(self.columns);
+        let (indices, values) = self.row_entries(row);
+        for (&column, &value) in indices.iter().zip(values) {
+            dense[column] = value;
+        }
+        dense
+    }
+
+    pub fn dense_column(&self, column: usize) -> SimdVector<T> {
+        SimdVector::from_vector(
+            (0..self.rows)
+                .map(|i| self.get(i, column).unwrap())
+                .collect(),
+        )
+    }
+
+    pub fn iter_row(&self) -> SparseLineIter<'_, T> {
+        SparseLineIter {
+            lines: Cow::Borrowed(self),
+            count: 0,
+        }
+    }
+
+    pub fn iter_column(&self) -> SparseLineIter<'_, T> {
+        SparseLineIter {
+            lines: Cow::Owned(self.transpose()),
+            count: 0,
+        }
+    }
+
+    /// Returns the transpose, distributing the entries of each row into the
+    /// rows of the result by a counting sort. Visiting the rows in order
+    /// leaves the indices of every output row sorted.
+    pub fn transpose(&self) -> CsrMatrix<T> {
+        let mut offsets = vec![0; self.columns + 1];
+        for &column in &self.indices {
+            offsets[column + 1] += 1;
+        }
+        prefix_sum(&mut offsets);
+
+        let mut next = offsets.clone();
+        let mut indices = vec![0; self.nnz()];
+        let mut values = vec![T::ZERO; self.nnz()];
+        for row in 0..self.rows {
+            let (columns, line) = self.row_entries(row);
+            for (&column, &value) in columns.iter().zip(line) {
+                let slot = next[column];
+                next[column] += 1;
+                indices[slot] = row;
+                values[slot] = value;
+            }
+        }
+        CsrMatrix {
+            rows: self.columns,
+            columns: self.rows,
+            offsets,
+            indices,
+            values,
+        }
+    }
+
+    pub fn scale(mut self, rhs: T) -> CsrMatrix<T> {
+        self.values.par_iter_mut().for_each(|i| *i = *i * rhs);
+        self
+    }
+
+    /// Sparse matrix-vector product. Rows are computed in parallel, each
+    /// gathering `LANES` elements of `rhs` at a time into a SIMD chunk to
+    /// multiply against the stored values.
+    pub fn try_mul_vec(&self, rhs: &SimdVector<T>) -> Result<SimdVector<T>, MatrixOpError> {
+        if rhs.len() != self.columns {
+            return Err(MatrixOpError::DimensionMismatch {
+                lhs: self.dimensions(),
+                rhs: (rhs.len(), 1),
+            });
+        }
+        let x = rhs.as_slice();
+        Ok(SimdVector::from_vector(
+            (0..self.rows)
+                .into_par_iter()
+                .map(|i| {
+                    let (indices, values) = self.row_entries(i);
+                    gather_dot(indices, values, x)
+                })
+                .collect(),
+        ))
+    }
+
+    /// Vector-matrix product `lhsᵀ * self`, scattering each row scaled by the
+    /// matching element of `lhs`.
+    pub fn try_vec_mul(&self, lhs: &SimdVector<T>) -> Result<SimdVector<T>, MatrixOpError> {
+        if lhs.len() != self.rows {
+            return Err(MatrixOpError::DimensionMismatch {
+                lhs: (1, lhs.len()),
+                rhs: self.dimensions(),
+            });
+        }
+        let mut out = SimdVector::zeros(self.columns);
+        for (row, factor) in lhs.iter().enumerate() {
+            let (indices, values) = self.row_entries(row);
+            for (&column, &value) in indices.iter().zip(values) {
+                out[column] = out[column] + factor * value;
+            }
+        }
+        Ok(out)
+    }
+
+    /// Sparse times dense matrix product. Every output row is a sum of rows
+    /// of `rhs`, one SIMD `axpy` per stored entry, and the rows are computed
+    /// in parallel.
+    pub fn try_mul_dense(&self, rhs: &SimdMatrix<T>) -> Result<SimdMatrix<T>, MatrixOpError> {
+        if rhs.height() != self.columns {
+            return Err(MatrixOpError::DimensionMismatch {
+                lhs: self.dimensions(),
+                rhs: rhs.dimensions(),
+            });
+        }
+        Ok(backend::with_current(|backend| SimdMatrix {
+            matrix: (0..self.rows)
+                .into_par_iter()
+                .map(|i| {
+                    let mut row = SimdVector::zeros(rhs.row_size);
+                    let (indices, values) = self.row_entries(i);
+                    for (&k, &value) in indices.iter().zip(values) {
+                        backend.axpy(value, &rhs.matrix[k], &mut row);
+                    }
+                    row
+                })
+                .collect(),
+            row_size: rhs.row_size,
+        }))
+    }
+
+    /// Sparse times sparse product by Gustavson's row-by-row algorithm, with a
+    /// dense accumulator the width of `rhs`. Entries that cancel to zero are
+    /// still stored.
+    pub fn try_mul_sparse(&self, rhs: &CsrMatrix<T>) -> Result<CsrMatrix<T>, MatrixOpError> {
+        if rhs.rows != self.columns {
+            return Err(MatrixOpError::DimensionMismatch {
+                lhs: self.dimensions(),
+                rhs: rhs.dimensions(),
+            });
+        }
+        let mut accumulator = vec![T::ZERO; rhs.columns];
+        let mut occupied = vec![false; rhs.columns];
+        let mut offsets = Vec::with_capacity(self.rows + 1);
+        let mut indices = vec![];
+        let mut values = vec![];
+        offsets.push(0);
+        for row in 0..self.rows {
+            let start = indices.len();
+            let (inner, factors) = self.row_entries(row);
+            for (&k, &factor) in inner.iter().zip(factors) {
+                let (columns, line) = rhs.row_entries(k);
+                for (&column, &value) in columns.iter().zip(line) {
+                    if !occupied[column] {
+                        occupied[column] = true;
+                        indices.push(column);
+                    }
+                    accumulator[column] = accumulator[column] + factor * value;
+                }
+            }
+            indices[start..].sort_unstable();
+            for &column in &indices[start..] {
+                values.push(accumulator[column]);
+                accumulator[column] = T::ZERO;
+                occupied[column] = false;
+            }
+            offsets.push(indices.len());
+        }
+        Ok(CsrMatrix {
+            rows: self.rows,
+            columns: rhs.columns,
+            offsets,
+            indices,
+            values,
+        })
+    }
+
+    fn line(&self, row: usize) -> Range<usize> {
+        self.offsets[row]..self.offsets[row + 1]
+    }
+}
+
+/// `Σ values[k] * x[indices[k]]`, a SIMD chunk at a time with a scalar tail.
+fn gather_dot<T: SimdScalar>(indices: &[usize], values: &[T], x: &[T]) -> T {
+    let mut sum: Simd<T, LANES> = Simd::splat(T::ZERO);
+    let index_chunks = indices.chunks_exact(LANES);
+    let value_chunks = values.chunks_exact(LANES);
+    let tail = index_chunks
+        .remainder()
+        .iter()
+        .zip(value_chunks.remainder())
+        .fold(T::ZERO, |acc, (&i, &value)| acc + value * x[i]);
+    for (i, value) in index_chunks.zip(value_chunks) {
+        let gathered = Simd::from_array(std::array::from_fn(|lane| x[i[lane]]));
+        sum = T::simd_add(sum, T::simd_mul(Simd::from_slice(value), gathered));
+    }
+    T::simd_reduce_sum(sum) + tail
+}
+
+impl<T: SimdScalar> CscMatrix<T> {
+    /// Builds a matrix from raw compressed columns: the entries of column `j`
+    /// are `indices[offsets[j]..offsets[j + 1]]`, holding strictly increasing
+    /// row indices.
+    pub fn try_new(
+        rows: usize,
+        columns: usize,
+        offsets: Vec<usize>,
+        indices: Vec<usize>,
+        values: Vec<T>,
+    ) -> Result<CscMatrix<T>, SparseError> {
+        let transpose =
+            CsrMatrix::try_new(columns, rows, offsets, indices, values).map_err(|e| match e {
+                SparseError::OutOfBounds {
+                    row,
+                    column,
+                    rows,
+                    columns,
+                } => SparseError::OutOfBounds {
+                    row: column,
+                    column: row,
+                    rows: columns,
+                    columns: rows,
+                },
+                e => e,
+            })?;
+        Ok(CscMatrix { transpose })
+    }
+
+    pub fn from_dense(dense: &SimdMatrix<T>) -> CscMatrix<T> {
+        CscMatrix {
+            transpose: CsrMatrix::from_dense(&dense.transpose()),
+        }
+    }
+
+    pub fn to_dense(&self) -> SimdMatrix<T> {
+        self.transpose.to_dense().transpose()
+    }
+
+    pub fn to_coo(&self) -> CooMatrix<T> {
+        let transposed = self.transpose.to_coo();
+        CooMatrix {
+            rows: transposed.columns,
+            columns: transposed.rows,
+            row_indices: transposed.column_indices,
+            column_indices: transposed.row_indices,
+            values: transposed.values,
+        }
+    }
+
+    pub fn to_csr(&self) -> CsrMatrix<T> {
+        self.transpose.transpose()
+    }
+
+    pub fn dimensions(&self) -> (usize, usize) {
+        let (columns, rows) = self.transpose.dimensions();
+        (rows, columns)
+    }
+
+    pub fn nnz(&self) -> usize {
+        self.transpose.nnz()
+    }
+
+    pub fn offsets(&self) -> &[usize] {
+        self.transpose.offsets()
+    }
+
+    pub fn indices(&self) -> &[usize] {
+        self.transpose.indices()
+    }
+
+    pub fn values(&self) -> &[T] {
+        self.transpose.values()
+    }
+
+    /// The row indices and values stored in `column`.
+    pub fn column_entries(&self, column: usize) -> (&[usize], &[T]) {
+        self.transpose.row_entries(column)
+    }
+
+    pub fn get(&self, row: usize, column: usize) -> Option<T> {
+        self.transpose.get(column, row)
+    }
+
+    pub fn dense_row(&self, row: usize) -> SimdVector<T> {
+        self.transpose.dense_column(row)
+    }
+
+    pub fn dense_column(&self, column: usize) -> SimdVector<T> {
+        self.transpose.dense_row(column)
+    }
+
+    pub fn iter_row(&self) -> SparseLineIter<'_, T> {
+        self.transpose.iter_column()
+    }
+
+    pub fn iter_column(&self) -> SparseLineIter<'_, T> {
+        self.transpose.iter_row()
+    }
+
+    pub fn transpose(&self) -> CscMatrix<T> {
+        CscMatrix {
+            transpose: self.transpose.transpose(),
+        }
+    }
+
+    pub fn scale(self, rhs: T) -> CscMatrix<T> {
+        CscMatrix {
+            transpose: self.transpose.scale(rhs),
+        }
+    }
+
+    /// Sparse matrix-vector product, scattering each column scaled by the
+    /// matching element of `rhs`.
+    pub fn try_mul_vec(&self, rhs: &SimdVector<T>) -> Result<SimdVector<T>, MatrixOpError> {
+        self.transpose
+            .try_vec_mul(rhs)
+            .map_err(|_| MatrixOpError::DimensionMismatch {
+                lhs: self.dimensions(),
+                rhs: (rhs.len(), 1),
+            })
+    }
+
+    /// Vector-matrix product `lhsᵀ * self`, one SIMD gather and dot product
+    /// per column.
+    pub fn try_vec_mul(&self, lhs: &SimdVector<T>) -> Result<SimdVector<T>, MatrixOpError> {
+        self.transpose
+            .try_mul_vec(lhs)
+            .map_err(|_| MatrixOpError::DimensionMismatch {
+                lhs: (1, lhs.len()),
+                rhs: self.dimensions(),
+            })
+    }
+
+    /// Sparse times dense matrix product, adding each stored entry's multiple
+    /// of a row of `rhs` into the output with a SIMD `axpy`.
+    pub fn try_mul_dense(&self, rhs: &SimdMatrix<T>) -> Result<SimdMatrix<T>, MatrixOpError> {
+        let (rows, columns) = self.dimensions();
+        if rhs.height() != columns {
+            return Err(MatrixOpError::DimensionMismatch {
+                lhs: self.dimensions(),
+                rhs: rhs.dimensions(),
+            });
+        }
+        let mut out = SimdMatrix::zeros(rows, rhs.row_size);
+        for (column, rhs_row) in rhs.matrix.iter().enumerate() {
+            let (indices, values) = self.column_entries(column);
+            for (&row, &value) in indices.iter().zip(values) {
+                out.matrix[row].axpy(value, rhs_row);
+            }
+        }
+        Ok(out)
+    }
+
+    /// Sparse times sparse product, through `(AB)ᵀ = BᵀAᵀ` on the stored
+    /// transposes.
+    pub fn try_mul_sparse(&self, rhs: &CscMatrix<T>) -> Result<CscMatrix<T>, MatrixOpError> {
+        Ok(CscMatrix {
+            transpose: rhs.transpose.try_mul_sparse(&self.transpose).map_err(|_| {
+                MatrixOpError::DimensionMismatch {
+                    lhs: self.dimensions(),
+                    rhs: rhs.dimensions(),
+                }
+            })?,
+        })
+    }
+}
+
+impl<'a, T: SimdScalar> Iterator for SparseLineIter<'a, T> {
+    type Item = SimdVector<T>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.count >= self.lines.rows {
+            return None;
+        }
+        self.count += 1;
+        Some(self.lines.dense_row(self.count - 1))
+    }
+}
@@ -1,448 +1,443 @@
 use std::{
-    ops::{Add, Div, Mul, Sub},
-    simd::{f32x1, f32x16, f32x2, f32x32, f32x4, f32x64, f32x8, SimdFloat},
+    ops::{Add, AddAssign, Div, DivAssign, Index, IndexMut, Mul, MulAssign, Neg, Sub, SubAssign},
+    slice::SliceIndex,
 };
 
+use num::Float;
 use rayon::prelude::*;
+use thiserror::Error;
 
+use crate::{
+    backend::{self, ElementwiseOp},
+    simd::Simd,
+};
+
+use super::scalar::SimdScalar;
+
+/// Number of elements held by each SIMD chunk of a [`SimdVector`].
+pub const LANES: usize = 16;
+
+/// A vector stored as one contiguous, SIMD-aligned buffer of `Simd<T, LANES>`
+/// chunks.
+///
+/// The last chunk is padded out to `LANES` elements. Padding lanes always hold
+/// `T::ZERO`, so reductions and elementwise products can run over whole chunks
+/// without masking, and two vectors with the same elements compare equal.
+///
+/// The elementwise operators (`+`, `-`, `*`, `/` between two vectors) panic
+/// when the lengths differ, since a mismatch is always a bug in the calling
+/// code. Use `checked_add`, `checked_sub`, `checked_mul` or `checked_div` to
+/// get a [`VectorOpError`] instead.
 #[derive(Debug, Clone, PartialEq)]
-pub struct SimdVector {
-    pub size_64: Vec<f32x64>,
-    pub size_32: Option<f32x32>,
-    pub size_16: Option<f32x16>,
-    pub size_8: Option<f32x8>,
-    pub size_4: Option<f32x4>,
-    pub size_2: Option<f32x2>,
-    pub size_1: Option<f32x1>,
+pub struct SimdVector<T: SimdScalar = f32> {
+    chunks: Vec<Simd<T, LANES>>,
+    len: usize,
 }
 
-pub struct VectorIter<'a> {
-    parent: &'a SimdVector,
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+pub enum VectorOpError {
+    #[error("Elementwise operations need vectors of equal length, but the left hand side has {lhs} elements and the right hand side has {rhs}")]
+    LengthMismatch { lhs: usize, rhs: usize },
+}
+
+pub struct VectorIter<'a, T: SimdScalar = f32> {
+    parent: &'a SimdVector<T>,
     count: usize,
 }
 
-impl SimdVector {
+impl<T: SimdScalar> SimdVector<T> {
     pub fn len(&self) -> usize {
-        self.size_1.map_or(0, |_i| 1)
-            + self.size_2.map_or(0, |i| i.lanes())
-            + self.size_4.map_or(0, |i| i.lanes())
-            + self.size_8.map_or(0, |i| i.lanes())
-            + self.size_16.map_or(0, |i| i.lanes())
-            + self.size_32.map_or(0, |i| i.lanes())
-            + self.size_64.len() * 64
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
     }
 
-    pub fn iter(&self) -> VectorIter {
+    pub fn iter(&self) -> VectorIter<'_, T> {
         VectorIter {
             parent: (self),
             count: (0),
         }
     }
 
-    pub fn sum(&self) -> f32 {
-        self.size_1.map_or(0., |i| i.reduce_sum())
-            + self.size_2.map_or(0., |i| i.reduce_sum())
-            + self.size_4.map_or(0., |i| i.reduce_sum())
-            + self.size_8.map_or(0., |i| i.reduce_sum())
-            + self.size_16.map_or(0., |i| i.reduce_sum())
-            + self.size_32.map_or(0., |i| i.reduce_sum())
-            + self
-                .size_64
-                .iter()
-                .fold(f32x64::splat(0.), |i, j| i + j)
-                .reduce_sum()
+    /// The underlying chunks, including the zero padding of the last one.
+    pub fn chunks(&self) -> &[Simd<T, LANES>] {
+        &self.chunks
+    }
+
+    /// Mutable access to the chunks. Callers must leave the padding lanes
+    /// zeroed, or call `clear_padding` afterwards.
+    pub(crate) fn chunks_mut(&mut self) -> &mut [Simd<T, LANES>] {
+        &mut self.chunks
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        // SAFETY: `Simd<T, N>` is exactly the size of `[T; N]`, so the chunk
+        // buffer is `chunks.len() * LANES` initialized, contiguous `T`s, and
+        // `len` never exceeds that.
+        unsafe { std::slice::from_raw_parts(self.chunks.as_ptr().cast::<T>(), self.len) }
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        // SAFETY: see `as_slice`. The slice stops at `len`, so the padding
+        // lanes cannot be written through it.
+        unsafe { std::slice::from_raw_parts_mut(self.chunks.as_mut_ptr().cast::<T>(), self.len) }
+    }
+
+    pub fn sum(&self) -> T {
+        backend::with_current(|i| i.sum(self))
+    }
+
+    pub fn scale(mut self, rhs: T) -> SimdVector<T> {
+        self *= rhs;
+        self
     }
 
-    pub fn scale(self, rhs: f32) -> SimdVector {
+    /// Negates every element in place.
+    pub fn negate(&mut self) {
+        self.map_chunks_assign(T::simd_neg);
+    }
+
+    pub fn get(&self, index: usize) -> Option<T> {
+        self.as_slice().get(index).copied()
+    }
+
+    pub fn from_slice(slice: &[T]) -> SimdVector<T> {
         SimdVector {
-            size_64: self
-                .size_64
-                .par_iter()
-                .map(|i| i * f32x64::splat(rhs))
+            chunks: slice
+                .chunks(LANES)
+                .map(|i| {
+                    if i.len() == LANES {
+                        Simd::from_slice(i)
+                    } else {
+                        let mut lanes = [T::ZERO; LANES];
+                        lanes[..i.len()].copy_from_slice(i);
+                        Simd::from_array(lanes)
+                    }
+                })
                 .collect(),
-            size_32: self.size_32.map(|i| i * f32x32::splat(rhs)),
-            size_16: self.size_16.map(|i| i * f32x16::splat(rhs)),
-            size_8: self.size_8.map(|i| i * f32x8::splat(rhs)),
-            size_4: self.size_4.map(|i| i * f32x4::splat(rhs)),
-            size_2: self.size_2.map(|i| i * f32x2::splat(rhs)),
-            size_1: self.size_1.map(|i| i * f32x1::splat(rhs)),
+            len: slice.len(),
         }
     }
 
-    //TODO: fix faster get function
-    // fn bitmask(&self) -> usize {
-    //     self.size_32.map_or(0, |_i| 0b100000)
-    //         | self.size_16.map_or(0, |_i| 0b010000)
-    //         | self.size_8.map_or(0, |_i| 0b001000)
-    //         | self.size_4.map_or(0, |_i| 0b000100)
-    //         | self.size_2.map_or(0, |_i| 0b000010)
-    //         | self.size_1.map_or(0, |_i| 0b000001)
-    // }
-
-    // fn get_broken(&self, index: usize) -> Option<f32> {
-    //     if self.len() == 0 {
-    //         return None;
-    //     }
-    //     let bitmask = self.bitmask();
-    //     self.size_64
-    //         .iter()
-    //         .map(|i| i.as_array())
-    //         .flatten()
-    //         .collect::<Vec<&f32>>()
-    //         .get(index)
-    //         .copied()
-    //         .copied()
-    //         .or_else(|| {
-    //             let current = index - (64 * self.size_64.len());
-    //             let array = (current ^ bitmask).next_power_of_two() >> 1;
-    //             match array {
-    //                 32 => self.size_32.unwrap().as_array().get(current).copied(),
-    //                 16 => self
-    //                     .size_16
-    //                     .unwrap()
-    //                     .as_array()
-    //                     .get(current - (current << current.leading_ones()))
-    //                     .copied(),
-    //                 8 => self
-    //                     .size_8
-    //                     .unwrap()
-    //                     .as_array()
-    //                     .get(current - (bitmask & 0b110000))
-    //                     .copied(),
-    //                 4 => self
-    //                     .size_4
-    //                     .unwrap()
-    //                     .as_array()
-    //                     .get(current - (bitmask & 0b111000))
-    //                     .copied(),
-    //                 2 => self
-    //                     .size_2
-    //                     .unwrap()
-    //                     .as_array()
-    //                     .get(current - (bitmask & 0b111100))
-    //                     .copied(),
-    //                 1 => self
-    //                     .size_1
-    //                     .unwrap()
-    //                     .as_array()
-    //                     .get(current - (bitmask & 0b111110))
-    //                     .copied(),
-    //                 _ => None,
-    //             }
-    //         })
-    // }
-
-    pub fn get(&self, index: usize) -> Option<f32> {
-        self.size_64
-            .par_iter()
-            .map(|i| i.as_array())
-            .flatten()
-            .collect::<Vec<&f32>>()
-            .get(index)
-            .copied()
-            .copied()
-            .or_else(|| {
-                let mut current = index - (64 * self.size_64.len());
-                if self.size_32.is_some() {
-                    if current < 32 {
-                        return self.size_32.unwrap().as_array().get(current).copied();
-                    } else {
-                        current -= 32;
-                    }
-                }
-                if self.size_16.is_some() {
-                    if current < 16 {
-                        return self.size_16.unwrap().as_array().get(current).copied();
-                    } else {
-                        current -= 16;
-                    }
-                }
-                if self.size_8.is_some() {
-                    if current < 8 {
-                        return self.size_8.unwrap().as_array().get(current).copied();
-                    } else {
-                        current -= 8;
-                    }
-                }
-                if self.size_4.is_some() {
-                    if current < 4 {
-                        return self.size_4.unwrap().as_array().get(current).copied();
-                    } else {
-                        current -= 4;
-                    }
-                }
-                if self.size_2.is_some() {
-                    if current < 2 {
-                        return self.size_2.unwrap().as_array().get(current).copied();
-                    } else {
-                        current -= 2;
-                    }
-                }
-                if self.size_1.is_some() {
-                    if current < 1 {
-                        return self.size_1.unwrap().as_array().get(current).copied();
-                    } else {
-                        // current -= 1;
-                    }
-                }
-                None
-            })
+    pub fn from_vector(vec: Vec<T>) -> SimdVector<T> {
+        Self::from_slice(&vec)
     }
 
-    pub fn from_vector(vec: Vec<f32>) -> SimdVector {
-        let mut remainder = vec.chunks_exact(64).remainder();
+    pub fn zeros(len: usize) -> SimdVector<T> {
         SimdVector {
-            size_64: vec
-                .chunks_exact(64)
-                .map(|i| f32x64::from_slice(i))
-                .collect(),
-            size_32: remainder
-                .take(..32)
-                .map_or(None, |i| Some(f32x32::from_slice(i))),
-            size_16: remainder
-                .take(..16)
-                .map_or(None, |i| Some(f32x16::from_slice(i))),
-            size_8: remainder
-                .take(..8)
-                .map_or(None, |i| Some(f32x8::from_slice(i))),
-            size_4: remainder
-                .take(..4)
-                .map_or(None, |i| Some(f32x4::from_slice(i))),
-            size_2: remainder
-                .take(..2)
-                .map_or(None, |i| Some(f32x2::from_slice(i))),
-            size_1: remainder
-                .take_first()
-                .map_or(None, |i| Some(f32x1::from_slice(&[*i]))),
+            chunks: vec![Simd::splat(T::ZERO); len.div_ceil(LANES)],
+            len,
         }
     }
 
-    pub fn to_vector(&self) -> Vec<f32> {
-        let mut out = self
-            .size_64
-            .par_iter()
-            .map(|i| i.as_array())
-            .flatten()
-            .copied()
-            .collect::<Vec<f32>>();
-        out.extend_from_slice(
-            self.size_32
-                .map_or(vec![], |i| i.as_array().to_vec())
-                .as_slice(),
-        );
-        out.extend_from_slice(
-            self.size_16
-                .map_or(vec![], |i| i.as_array().to_vec())
-                .as_slice(),
-        );
-        out.extend_from_slice(
-            self.size_8
-                .map_or(vec![], |i| i.as_array().to_vec())
-                .as_slice(),
-        );
-        out.extend_from_slice(
-            self.size_4
-                .map_or(vec![], |i| i.as_array().to_vec())
-                .as_slice(),
-        );
-        out.extend_from_slice(
-            self.size_2
-                .map_or(vec![], |i| i.as_array().to_vec())
-                .as_slice(),
-        );
-        out.extend_from_slice(
-            self.size_1
-                .map_or(vec![], |i| i.as_array().to_vec())
-                .as_slice(),
-        );
-        out
+    /// `start, start + step, start + 2 * step, ...` up to but excluding `stop`.
+    /// Each element is computed from `start` directly rather than by repeated
+    /// addition, so float ranges do not drift. A zero step gives an empty
+    /// vector.
+    pub fn arange(start: T, stop: T, step: T) -> SimdVector<T> {
+        let in_range = |i: T| {
+            if step > T::ZERO {
+                i < stop
+            } else {
+                step < T::ZERO && i > stop
+            }
+        };
+        let mut out = vec![];
+        let mut count = T::ZERO;
+        let mut value = start;
+        while in_range(value) {
+            out.push(value);
+            count = count + T::ONE;
+            value = start + step * count;
+        }
+        Self::from_vector(out)
     }
-}
 
-impl Add for SimdVector {
-    type Output = Self;
+    pub fn to_vector(&self) -> Vec<T> {
+        self.as_slice().to_vec()
+    }
 
-    fn add(self, rhs: Self) -> Self::Output {
-        SimdVector {
-            size_64: self
-                .size_64
-                .par_iter()
-                .take(rhs.size_64.len())
-                .enumerate()
-                .map(|(i, j)| j + rhs.size_64.get(i).unwrap())
-                .collect(),
-            size_32: self.size_32.map_or(rhs.size_32, |i| {
-                rhs.size_32.map_or(self.size_32, |j| Some(i + j))
-            }),
-            size_16: self.size_16.map_or(rhs.size_16, |i| {
-                rhs.size_16.map_or(self.size_16, |j| Some(i + j))
-            }),
-            size_8: self.size_8.map_or(rhs.size_8, |i| {
-                rhs.size_8.map_or(self.size_8, |j| Some(i + j))
-            }),
-            size_4: self.size_4.map_or(rhs.size_4, |i| {
-                rhs.size_4.map_or(self.size_4, |j| Some(i + j))
-            }),
-            size_2: self.size_2.map_or(rhs.size_2, |i| {
-                rhs.size_2.map_or(self.size_2, |j| Some(i + j))
-            }),
-            size_1: self.size_1.map_or(rhs.size_1, |i| {
-                rhs.size_1.map_or(self.size_1, |j| Some(i + j))
-            }),
+    pub fn checked_add(&self, rhs: &SimdVector<T>) -> Result<SimdVector<T>, VectorOpError> {
+        self.checked_zip(rhs, ElementwiseOp::Add)
+    }
+
+    pub fn checked_sub(&self, rhs: &SimdVector<T>) -> Result<SimdVector<T>, VectorOpError> {
+        self.checked_zip(rhs, ElementwiseOp::Sub)
+    }
+
+    pub fn checked_mul(&self, rhs: &SimdVector<T>) -> Result<SimdVector<T>, VectorOpError> {
+        self.checked_zip(rhs, ElementwiseOp::Mul)
+    }
+
+    pub fn checked_div(&self, rhs: &SimdVector<T>) -> Result<SimdVector<T>, VectorOpError> {
+        self.checked_zip(rhs, ElementwiseOp::Div)
+    }
+
+    pub fn checked_dot(&self, rhs: &SimdVector<T>) -> Result<T, VectorOpError> {
+        self.check_len(rhs)?;
+        Ok(backend::with_current(|i| i.dot(self, rhs)))
+    }
+
+    /// `self += alpha * x`, in place. Panics if the lengths differ.
+    pub fn axpy(&mut self, alpha: T, x: &SimdVector<T>) {
+        if let Err(e) = self.check_len(x) {
+            panic!("{e}");
         }
+        backend::with_current(|i| i.axpy(alpha, x, self));
     }
-}
 
-impl Mul for SimdVector {
-    type Output = Self;
+    fn check_len(&self, rhs: &SimdVector<T>) -> Result<(), VectorOpError> {
+        if self.len == rhs.len {
+            Ok(())
+        } else {
+            Err(VectorOpError::LengthMismatch {
+                lhs: self.len,
+                rhs: rhs.len,
+            })
+        }
+    }
 
-    fn mul(self, rhs: Self) -> Self::Output {
-        SimdVector {
-            size_64: self
-                .size_64
-                .par_iter()
-                .take(rhs.size_64.len())
-                .enumerate()
-                .map(|(i, j)| j * rhs.size_64.get(i).unwrap())
-                .collect(),
-            size_32: self.size_32.map_or(rhs.size_32, |i| {
-                rhs.size_32.map_or(self.size_32, |j| Some(j * i))
-            }),
-            size_16: self.size_16.map_or(rhs.size_16, |i| {
-                rhs.size_16.map_or(self.size_16, |j| Some(j * i))
-            }),
-            size_8: self.size_8.map_or(rhs.size_8, |i| {
-                rhs.size_8.map_or(self.size_8, |j| Some(j * i))
-            }),
-            size_4: self.size_4.map_or(rhs.size_4, |i| {
-                rhs.size_4.map_or(self.size_4, |j| Some(j * i))
-            }),
-            size_2: self.size_2.map_or(rhs.size_2, |i| {
-                rhs.size_2.map_or(self.size_2, |j| Some(j * i))
-            }),
-            size_1: self.size_1.map_or(rhs.size_1, |i| {
-                rhs.size_1.map_or(self.size_1, |j| Some(j * i))
-            }),
+    /// Restores the zero padding after an operation that may have written
+    /// garbage into it, e.g. the `0. * inf` or `0. / 0.` of a padding lane.
+    pub(crate) fn clear_padding(&mut self) {
+        let used = self.len % LANES;
+        if used != 0 {
+            if let Some(last) = self.chunks.last_mut() {
+                last.as_mut_array()[used..].fill(T::ZERO);
+            }
         }
     }
-}
 
-impl Div for SimdVector {
-    type Output = Self;
+    pub(crate) fn map_chunks_assign(
+        &mut self,
+        f: impl Fn(Simd<T, LANES>) -> Simd<T, LANES> + Sync,
+    ) {
+        self.chunks.par_iter_mut().for_each(|i| *i = f(*i));
+        self.clear_padding();
+    }
 
-    fn div(self, rhs: Self) -> Self::Output {
-        SimdVector {
-            size_64: self
-                .size_64
-                .par_iter()
-                .take(rhs.size_64.len())
-                .enumerate()
-                .map(|(i, j)| j / rhs.size_64.get(i).unwrap())
-                .collect(),
-            size_32: self.size_32.map_or(rhs.size_32, |i| {
-                rhs.size_32.map_or(self.size_32, |j| Some(i / j))
-            }),
-            size_16: self.size_16.map_or(rhs.size_16, |i| {
-                rhs.size_16.map_or(self.size_16, |j| Some(i / j))
-            }),
-            size_8: self.size_8.map_or(rhs.size_8, |i| {
-                rhs.size_8.map_or(self.size_8, |j| Some(i / j))
-            }),
-            size_4: self.size_4.map_or(rhs.size_4, |i| {
-                rhs.size_4.map_or(self.size_4, |j| Some(i / j))
-            }),
-            size_2: self.size_2.map_or(rhs.size_2, |i| {
-                rhs.size_2.map_or(self.size_2, |j| Some(i / j))
-            }),
-            size_1: self.size_1.map_or(rhs.size_1, |i| {
-                rhs.size_1.map_or(self.size_1, |j| Some(i / j))
-            }),
+    /// Applies `f` chunkwise to two vectors of equal length, writing into
+    /// `self`, with the padding lanes of `rhs` replaced by `rhs_padding`.
+    pub(crate) fn zip_chunks_assign(
+        &mut self,
+        rhs: &SimdVector<T>,
+        rhs_padding: T,
+        f: impl Fn(Simd<T, LANES>, Simd<T, LANES>) -> Simd<T, LANES> + Sync,
+    ) {
+        let last = self.chunks.len().saturating_sub(1);
+        let used = self.len % LANES;
+        self.chunks
+            .par_iter_mut()
+            .zip(rhs.chunks.par_iter())
+            .enumerate()
+            .for_each(|(n, (i, j))| {
+                let mut j = *j;
+                if n == last && used != 0 {
+                    j.as_mut_array()[used..].fill(rhs_padding);
+                }
+                *i = f(*i, j);
+            });
+        self.clear_padding();
+    }
+
+    fn checked_zip(
+        &self,
+        rhs: &SimdVector<T>,
+        op: ElementwiseOp,
+    ) -> Result<SimdVector<T>, VectorOpError> {
+        self.check_len(rhs)?;
+        let mut out = self.clone();
+        backend::with_current(|i| i.elementwise(op, &mut out, rhs));
+        Ok(out)
+    }
+}
+
+impl<T: SimdScalar + Float> SimdVector<T> {
+    /// `len` evenly spaced values from `start` to `stop`, both inclusive.
+    pub fn linspace(start: T, stop: T, len: usize) -> SimdVector<T> {
+        if len == 1 {
+            return Self::from_vector(vec![start]);
+        }
+        let step = (stop - start) / T::from(len.saturating_sub(1)).unwrap();
+        let mut out: Vec<T> = (0..len)
+            .map(|i| start + step * T::from(i).unwrap())
+            .collect();
+        if let Some(last) = out.last_mut() {
+            *last = stop;
         }
+        Self::from_vector(out)
     }
 }
 
-impl Sub for SimdVector {
-    type Output = Self;
+impl<T: SimdScalar, I: SliceIndex<[T]>> Index<I> for SimdVector<T> {
+    type Output = I::Output;
 
-    fn sub(self, rhs: Self) -> Self::Output {
-        SimdVector {
-            size_64: self
-                .size_64
-                .par_iter()
-                .take(rhs.size_64.len())
-                .enumerate()
-                .map(|(i, j)| j - rhs.size_64.get(i).unwrap())
-                .collect(),
-            size_32: self.size_32.map_or(rhs.size_32, |i| {
-                rhs.size_32.map_or(self.size_32, |j| Some(i - j))
-            }),
-            size_16: self.size_16.map_or(rhs.size_16, |i| {
-                rhs.size_16.map_or(self.size_16, |j| Some(i - j))
-            }),
-            size_8: self.size_8.map_or(rhs.size_8, |i| {
-                rhs.size_8.map_or(self.size_8, |j| Some(i - j))
-            }),
-            size_4: self.size_4.map_or(rhs.size_4, |i| {
-                rhs.size_4.map_or(self.size_4, |j| Some(i - j))
-            }),
-            size_2: self.size_2.map_or(rhs.size_2, |i| {
-                rhs.size_2.map_or(self.size_2, |j| Some(i - j))
-            }),
-            size_1: self.size_1.map_or(rhs.size_1, |i| {
-                rhs.size_1.map_or(self.size_1, |j| Some(i - j))
-            }),
+    fn index(&self, index: I) -> &Self::Output {
+        &self.as_slice()[index]
+    }
+}
+
+impl<T: SimdScalar, I: SliceIndex<[T]>> IndexMut<I> for SimdVector<T> {
+    fn index_mut(&mut self, index: I) -> &mut Self::Output {
+        &mut self.as_mut_slice()[index]
+    }
+}
+
+// Every elementwise operator goes through the in-place `*Assign<&SimdVector>`
+// form, so the owned variants reuse the left hand side's buffer and only the
+// `&a op &b` variants allocate.
+macro_rules! impl_elementwise_op {
+    ($op:ident, $fn:ident, $op_assign:ident, $fn_assign:ident, $checked:ident, $kind:ident) => {
+        impl<T: SimdScalar> $op_assign<&SimdVector<T>> for SimdVector<T> {
+            fn $fn_assign(&mut self, rhs: &SimdVector<T>) {
+                if let Err(e) = self.check_len(rhs) {
+                    panic!("{e}");
+                }
+                backend::with_current(|i| i.elementwise(ElementwiseOp::$kind, self, rhs));
+            }
+        }
+
+        impl<T: SimdScalar> $op_assign for SimdVector<T> {
+            fn $fn_assign(&mut self, rhs: SimdVector<T>) {
+                self.$fn_assign(&rhs);
+            }
         }
+
+        impl<T: SimdScalar> $op<&SimdVector<T>> for SimdVector<T> {
+            type Output = SimdVector<T>;
+
+            fn $fn(mut self, rhs: &SimdVector<T>) -> Self::Output {
+                self.$fn_assign(rhs);
+                self
+            }
+        }
+
+        impl<T: SimdScalar> $op for SimdVector<T> {
+            type Output = SimdVector<T>;
+
+            fn $fn(self, rhs: SimdVector<T>) -> Self::Output {
+                $op::$fn(self, &rhs)
+            }
+        }
+
+        impl<T: SimdScalar> $op<&SimdVector<T>> for &SimdVector<T> {
+            type Output = SimdVector<T>;
+
+            fn $fn(self, rhs: &SimdVector<T>) -> Self::Output {
+                self.$checked(rhs).unwrap_or_else(|e| panic!("{e}"))
+            }
+        }
+
+        impl<T: SimdScalar> $op<SimdVector<T>> for &SimdVector<T> {
+            type Output = SimdVector<T>;
+
+            fn $fn(self, rhs: SimdVector<T>) -> Self::Output {
+                $op::$fn(self, &rhs)
+            }
+        }
+    };
+}
+
+impl_elementwise_op!(Add, add, AddAssign, add_assign, checked_add, Add);
+impl_elementwise_op!(Sub, sub, SubAssign, sub_assign, checked_sub, Sub);
+impl_elementwise_op!(Mul, mul, MulAssign, mul_assign, checked_mul, Mul);
+impl_elementwise_op!(Div, div, DivAssign, div_assign, checked_div, Div);
+
+impl<T: SimdScalar> MulAssign<T> for SimdVector<T> {
+    fn mul_assign(&mut self, rhs: T) {
+        backend::with_current(|i| i.scale(rhs, self));
+    }
+}
+
+impl<T: SimdScalar> DivAssign<T> for SimdVector<T> {
+    fn div_assign(&mut self, rhs: T) {
+        let divisor = Simd::splat(rhs);
+        self.map_chunks_assign(|i| T::simd_div(i, divisor));
+    }
+}
+
+impl<T: SimdScalar> Div<T> for SimdVector<T> {
+    type Output = SimdVector<T>;
+
+    fn div(mut self, rhs: T) -> Self::Output {
+        self /= rhs;
+        self
+    }
+}
+
+impl<T: SimdScalar> Mul<T> for &SimdVector<T> {
+    type Output = SimdVector<T>;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        self.clone() * rhs
+    }
+}
+
+impl<T: SimdScalar> Div<T> for &SimdVector<T> {
+    type Output = SimdVector<T>;
+
+    fn div(self, rhs: T) -> Self::Output {
+        self.clone() / rhs
+    }
+}
+
+impl<T: SimdScalar> Neg for SimdVector<T> {
+    type Output = SimdVector<T>;
+
+    fn neg(mut self) -> Self::Output {
+        self.negate();
+        self
+    }
+}
+
+impl<T: SimdScalar> Neg for &SimdVector<T> {
+    type Output = SimdVector<T>;
+
+    fn neg(self) -> Self::Output {
+        -self.clone()
     }
 }
 
-impl<'a> Iterator for VectorIter<'a> {
-    type Item = f32;
+impl<'a, T: SimdScalar> Iterator for VectorIter<'a, T> {
+    type Item = T;
     fn next(&mut self) -> Option<Self::Item> {
+        let val = self.parent.get(self.count);
         self.count += 1;
-        self.parent.get(self.count)
+        val
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.parent.len().saturating_sub(self.count);
+        (remaining, Some(remaining))
     }
 }
 
-impl Mul<f32> for SimdVector {
-    type Output = SimdVector;
+impl<'a, T: SimdScalar> ExactSizeIterator for VectorIter<'a, T> {}
 
-    fn mul(self, rhs: f32) -> Self::Output {
-        SimdVector {
-            size_64: self
-                .size_64
-                .as_slice()
-                .par_iter()
-                .map(|i| i * f32x64::splat(rhs))
-                .collect(),
-            size_32: self.size_32.map(|i| i * f32x32::splat(rhs)),
-            size_16: self.size_16.map(|i| i * f32x16::splat(rhs)),
-            size_8: self.size_8.map(|i| i * f32x8::splat(rhs)),
-            size_4: self.size_4.map(|i| i * f32x4::splat(rhs)),
-            size_2: self.size_2.map(|i| i * f32x2::splat(rhs)),
-            size_1: self.size_1.map(|i| i * f32x1::splat(rhs)),
-        }
+impl<T: SimdScalar> Mul<T> for SimdVector<T> {
+    type Output = SimdVector<T>;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        self.scale(rhs)
     }
 }
 
-impl Mul<SimdVector> for f32 {
-    type Output = SimdVector;
+// `impl<T> Mul<SimdVector<T>> for T` is rejected by the orphan rules, so the
+// scalar-on-the-left product is spelled out per element type.
+macro_rules! impl_scalar_lhs_mul {
+    ($($t:ty),*) => {
+        $(
+            impl Mul<SimdVector<$t>> for $t {
+                type Output = SimdVector<$t>;
 
-    fn mul(self, rh: SimdVector) -> Self::Output {
-        let rhs = self;
-        let slf = rh;
-        SimdVector {
-            size_64: slf
-                .size_64
-                .as_slice()
-                .par_iter()
-                .map(|i| i * f32x64::splat(rhs))
-                .collect(),
-            size_32: slf.size_32.map(|i| i * f32x32::splat(rhs)),
-            size_16: slf.size_16.map(|i| i * f32x16::splat(rhs)),
-            size_8: slf.size_8.map(|i| i * f32x8::splat(rhs)),
-            size_4: slf.size_4.map(|i| i * f32x4::splat(rhs)),
-            size_2: slf.size_2.map(|i| i * f32x2::splat(rhs)),
-            size_1: slf.size_1.map(|i| i * f32x1::splat(rhs)),
-        }
-    }
+                fn mul(self, rhs: SimdVector<$t>) -> Self::Output {
+                    rhs * self
+                }
+            }
+        )*
+    };
 }
+
+impl_scalar_lhs_mul!(f32, f64, i8, i16, i32, i64, u8, u16, u32, u64);